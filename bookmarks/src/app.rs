@@ -1,73 +1,476 @@
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+use tokio::sync::{watch, Notify};
 use warp::Filter;
 
-use crate::application::{ApplicationService, ApplicationServiceImpl};
-use crate::domain::bookmark::{BookmarkRepository, BookmarkRepositoryError};
-use crate::ports::http::warp::{bookmarks_search_filter, bookmarks_suggestions_filter};
-use crate::ports::persistence::file_system::{
-    FileSystemBookmarkRepositoryAdapter, FileSystemBookmarkRepositoryAdapterError,
+use uuid::Uuid;
+
+use crate::application::{
+    ApplicationService, ApplicationServiceError, ApplicationServiceImpl, BookmarkEditService,
+    ReloadService,
+};
+use crate::config::{AppConfig, AppConfigError};
+use crate::domain::bookmark::{
+    Bookmark, BookmarkChange, BookmarkRepository, BookmarkRepositoryError,
+    BookmarkVisitRepositoryError, Editor,
 };
+use crate::ports::file_watch::{NotifyFileWatcher, NotifyFileWatcherError};
+use crate::ports::http::warp::{
+    admin_bookmark_list_filter, admin_bookmarks_filter, admin_reload_filter, bookmark_visit_filter,
+    bookmarks_export_filter, bookmarks_search_filter, bookmarks_suggestions_filter,
+};
+use crate::ports::persistence::file_system::{refresh_remote_source, refresh_remote_sources};
+use crate::ports::persistence::sqlite::SqliteBookmarkVisitRepositoryAdapter;
+use crate::ports::persistence::ConfiguredBookmarkRepository;
 use crate::ports::search::tantivy::{
-    TantivyBookmarkSearchEngineAdapter, TantivyBookmarkSearchEngineAdapterError,
+    remove_retired_index, TantivyBookmarkSearchEngineAdapter,
+    TantivyBookmarkSearchEngineAdapterError,
 };
 
-#[derive(Default)]
+type ServedApplicationService = ApplicationServiceImpl<
+    ConfiguredBookmarkRepository,
+    TantivyBookmarkSearchEngineAdapter,
+    SqliteBookmarkVisitRepositoryAdapter,
+>;
+
+#[derive(Default, Clone)]
 pub struct App {
     search_engine_config_path: PathBuf,
+    reload_requests: Arc<Notify>,
+    file_changes: Arc<Notify>,
+    shutdown_requests: Arc<Notify>,
 }
 
 impl App {
     pub fn new(search_engine_config_path: PathBuf) -> Self {
         App {
             search_engine_config_path,
+            reload_requests: Arc::new(Notify::new()),
+            file_changes: Arc::new(Notify::new()),
+            shutdown_requests: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn reload_trigger(&self) -> ReloadTrigger {
+        ReloadTrigger {
+            reload_requests: self.reload_requests.clone(),
+        }
+    }
+
+    pub fn shutdown_trigger(&self) -> ShutdownTrigger {
+        ShutdownTrigger {
+            shutdown_requests: self.shutdown_requests.clone(),
         }
     }
 
     pub async fn run(&self) -> Result<(), AppInitialisationError> {
-        let application_service = ApplicationServiceImpl::new(self.bookmark_search_engine()?);
+        self.serve(|_| {}).await
+    }
+
+    /// Serves on the configured address, passing the one actually bound to `on_listening`.
+    async fn serve<F>(&self, on_listening: F) -> Result<(), AppInitialisationError>
+    where
+        F: FnOnce(SocketAddr),
+    {
+        let config = self.config()?;
+        if let Err(err) = ConfiguredBookmarkRepository::pull(config.repository()) {
+            log::warn!("{}, serving the local bookmarks", err);
+        }
+        refresh_remote_sources(config.bookmark_sources());
+        let (bookmark_repository, bookmark_search_engine) = self.bookmarks(&config)?;
+        let bookmark_files = local_bookmark_files(&config, bookmark_repository.files());
+        let bookmark_visits = SqliteBookmarkVisitRepositoryAdapter::open(
+            config.visits().path(),
+            config.visits().retention(),
+        )
+        .map_err(BookmarkVisitRepositoryError::from)
+        .map_err(map_initialisation_error_cause)?;
+        let application_service = Arc::new(
+            ApplicationServiceImpl::new(
+                bookmark_repository,
+                bookmark_search_engine,
+                bookmark_visits,
+            )
+            .with_visit_tracking(config.visits().enabled()),
+        );
+        remove_retired_index(config.search().index_directory());
+        let reload_service = Arc::new(AppReloadService {
+            app: self.clone(),
+            application_service: application_service.clone(),
+            reload_lock: Mutex::new(()),
+            watched_files: Mutex::new(None),
+        });
+        reload_service
+            .watch(bookmark_files)
+            .map_err(map_initialisation_error_cause)?;
+        self.handle_reload_requests(reload_service.clone());
+        self.poll_remote_sources(&config, reload_service.clone());
+
+        let routes = self.routes(&config, application_service, reload_service.clone());
+        let shutdown = self.shutdown_signal();
+        let mut server_shutdown = shutdown.clone();
+        let (address, server) = warp::serve(routes)
+            .try_bind_with_graceful_shutdown(config.server().address(), async move {
+                wait_for_shutdown(&mut server_shutdown).await
+            })
+            .map_err(map_initialisation_error_cause)?;
+        log::info!("Listening on {}", address);
+        on_listening(address);
+
+        let mut drain_shutdown = shutdown;
+        let drain_timeout = config.server().shutdown_timeout();
+        tokio::select! {
+            _ = server => log::info!("Finished serving in-flight requests"),
+            _ = async {
+                wait_for_shutdown(&mut drain_shutdown).await;
+                tokio::time::sleep(drain_timeout).await
+            } => log::warn!(
+                "Stopped waiting for in-flight requests after {} seconds",
+                drain_timeout.as_secs()
+            ),
+        }
+
+        let _ = tokio::task::spawn_blocking(move || reload_service.wait_for_pending_reload()).await;
+        log::info!("Shut down");
+
+        Ok(())
+    }
+
+    fn shutdown_signal(&self) -> watch::Receiver<bool> {
+        let (sender, receiver) = watch::channel(false);
+        let shutdown_requests = self.shutdown_requests.clone();
+        tokio::spawn(async move {
+            shutdown_requests.notified().await;
+            log::info!("Shutting down, no longer accepting connections");
+            let _ = sender.send(true);
+        });
+        receiver
+    }
+
+    fn config(&self) -> Result<AppConfig, AppInitialisationError> {
+        AppConfig::load(self.search_engine_config_path.as_path())
+            .map_err(map_initialisation_error_cause)
+    }
+
+    fn watch_bookmark_files(
+        &self,
+        bookmark_files: &[PathBuf],
+    ) -> Result<NotifyFileWatcher, NotifyFileWatcherError> {
+        let mut watched_files = vec![self.search_engine_config_path.as_path()];
+        watched_files.extend(bookmark_files.iter().map(PathBuf::as_path));
+        let file_changes = self.file_changes.clone();
+        NotifyFileWatcher::watch(watched_files.as_slice(), move || file_changes.notify_one())
+    }
 
-        warp::serve(self.routes(Arc::new(application_service)))
-            .run(([127, 0, 0, 1], 3033))
-            .await;
+    /// Reloads on every explicit request or file change. Only explicit requests pull the git
+    /// repository.
+    fn handle_reload_requests(&self, reload_service: Arc<AppReloadService>) {
+        let reload_requests = self.reload_requests.clone();
+        let file_changes = self.file_changes.clone();
+        tokio::spawn(async move {
+            loop {
+                let is_requested = tokio::select! {
+                    _ = reload_requests.notified() => true,
+                    _ = file_changes.notified() => false,
+                };
+                let reload_service = reload_service.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    if is_requested {
+                        reload_service.reload()
+                    } else {
+                        reload_service.reload_local()
+                    }
+                })
+                .await;
+            }
+        });
+    }
+
+    /// Reloads whenever a polled remote source has changed.
+    fn poll_remote_sources(&self, config: &AppConfig, reload_service: Arc<AppReloadService>) {
+        for source in config.bookmark_sources() {
+            let (url, poll_interval) = match source.remote_file().and_then(|remote| {
+                remote
+                    .poll_interval()
+                    .filter(|poll_interval| !poll_interval.is_zero())
+                    .map(|poll_interval| (remote.url().clone(), poll_interval))
+            }) {
+                Some(remote) => remote,
+                None => continue,
+            };
+            log::info!("Polling {} every {} seconds", url, poll_interval.as_secs());
+            let source = source.clone();
+            let reload_service = reload_service.clone();
+            tokio::spawn(async move {
+                let mut polls = tokio::time::interval(poll_interval);
+                polls.tick().await;
+                loop {
+                    polls.tick().await;
+                    let polled_source = source.clone();
+                    let refreshed =
+                        tokio::task::spawn_blocking(move || refresh_remote_source(&polled_source))
+                            .await;
+                    match refreshed {
+                        Ok(Ok(true)) => {
+                            let reload_service = reload_service.clone();
+                            let _ =
+                                tokio::task::spawn_blocking(move || reload_service.reload_local())
+                                    .await;
+                        }
+                        Ok(Err(err)) => log::warn!("{}, keeping the previous bookmarks", err),
+                        Ok(Ok(false)) | Err(_) => {}
+                    }
+                }
+            });
+        }
+    }
 
+    fn pull(&self) -> Result<(), AppReloadError> {
+        let config = self.config()?;
+        ConfiguredBookmarkRepository::pull(config.repository())
+            .map_err(map_initialisation_error_cause)?;
         Ok(())
     }
 
-    fn bookmark_search_engine(
+    /// Loads the bookmarks again, returning how many were loaded and the files to watch for
+    /// changes. The bookmarks being served are only replaced once all of them were loaded and
+    /// indexed.
+    fn reload(
         &self,
+        application_service: &ServedApplicationService,
+    ) -> Result<(usize, Vec<PathBuf>), AppReloadError> {
+        let config = self.config()?;
+        let (bookmark_repository, bookmark_search_engine) = self.bookmarks(&config)?;
+        let bookmark_count = bookmark_repository
+            .get_all()
+            .map_err(map_initialisation_error_cause)?
+            .len();
+        let bookmark_files = local_bookmark_files(&config, bookmark_repository.files());
+        application_service.replace_bookmarks(bookmark_repository, bookmark_search_engine);
+        remove_retired_index(config.search().index_directory());
+        Ok((bookmark_count, bookmark_files))
+    }
+
+    fn bookmarks(
+        &self,
+        config: &AppConfig,
     ) -> Result<
-        TantivyBookmarkSearchEngineAdapter<FileSystemBookmarkRepositoryAdapter>,
+        (
+            ConfiguredBookmarkRepository,
+            TantivyBookmarkSearchEngineAdapter,
+        ),
         AppInitialisationError,
     > {
-        let mut bookmark_repository =
-            FileSystemBookmarkRepositoryAdapter::new(self.search_engine_config_path.as_path());
-        bookmark_repository
-            .initialise()
-            .map_err(map_initialisation_error_cause)?;
+        let bookmark_repository = ConfiguredBookmarkRepository::load(
+            config.repository(),
+            config.bookmark_sources().clone(),
+            config.synonyms().clone(),
+        )
+        .map_err(map_initialisation_error_cause)?;
         let bookmarks = bookmark_repository
             .get_all()
             .map_err(map_initialisation_error_cause)?;
-        let mut search_engine = TantivyBookmarkSearchEngineAdapter::new(bookmark_repository);
+        let mut search_engine = TantivyBookmarkSearchEngineAdapter::new(
+            config.search().text_analysis().clone(),
+            config.synonyms().clone(),
+            config.search().index_directory().map(Path::to_path_buf),
+        )
+        .with_frecency(config.search().frecency());
         search_engine
             .initialise(bookmarks)
             .map_err(map_initialisation_error_cause)?;
-        Ok(search_engine)
+        Ok((bookmark_repository, search_engine))
     }
 
-    fn routes<AS>(
+    fn routes<AS, RS>(
         &self,
+        config: &AppConfig,
         application_service: Arc<AS>,
+        reload_service: Arc<RS>,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
     where
         AS: ApplicationService + Send + Sync,
+        RS: ReloadService + BookmarkEditService + Send + Sync + 'static,
     {
         let search = warp::path("search").and(bookmarks_search_filter(application_service.clone()));
-        let suggestions =
-            warp::path("suggestions").and(bookmarks_suggestions_filter(application_service));
+        let go = warp::path("go").and(bookmark_visit_filter(application_service.clone()));
+        let suggestions = warp::path("suggestions")
+            .and(bookmarks_suggestions_filter(application_service.clone()));
+        let export = warp::path!("export" / "bookmarks.html").and(bookmarks_export_filter(
+            application_service.clone(),
+            config.export().clone(),
+        ));
+        let admin_reload = warp::path!("admin" / "reload").and(admin_reload_filter(
+            reload_service.clone(),
+            config.admin().users(),
+        ));
+        let admin_bookmarks = warp::path!("admin" / "bookmarks" / ..).and(
+            admin_bookmark_list_filter(application_service, config.admin().users()).or(
+                admin_bookmarks_filter(reload_service, config.admin().users()),
+            ),
+        );
+
+        warp::any().and(
+            search
+                .or(go)
+                .or(suggestions)
+                .or(export)
+                .or(admin_reload)
+                .or(admin_bookmarks),
+        )
+    }
+}
+
+pub struct ReloadTrigger {
+    reload_requests: Arc<Notify>,
+}
+
+impl ReloadTrigger {
+    pub fn trigger(&self) {
+        self.reload_requests.notify_one();
+    }
+}
+
+pub struct ShutdownTrigger {
+    shutdown_requests: Arc<Notify>,
+}
+
+impl ShutdownTrigger {
+    pub fn trigger(&self) {
+        self.shutdown_requests.notify_one();
+    }
+}
 
-        warp::any().and(search.or(suggestions))
+/// The bookmark files to watch, leaving out the local copies of remote sources, which are reloaded
+/// when they are polled.
+fn local_bookmark_files(config: &AppConfig, bookmark_files: Vec<PathBuf>) -> Vec<PathBuf> {
+    bookmark_files
+        .into_iter()
+        .filter(|file| {
+            !config
+                .bookmark_sources()
+                .iter()
+                .any(|source| source.remote_file().is_some() && source.path() == file)
+        })
+        .collect()
+}
+
+async fn wait_for_shutdown(shutdown: &mut watch::Receiver<bool>) {
+    while !*shutdown.borrow() {
+        if shutdown.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+struct AppReloadService {
+    app: App,
+    application_service: Arc<ServedApplicationService>,
+    reload_lock: Mutex<()>,
+    watched_files: Mutex<Option<WatchedFiles>>,
+}
+
+struct WatchedFiles {
+    files: Vec<PathBuf>,
+    _watcher: NotifyFileWatcher,
+}
+
+impl AppReloadService {
+    fn wait_for_pending_reload(&self) {
+        let _reload_guard = self
+            .reload_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+
+    /// Reloads the bookmarks as they are on disk, after a bookmark file changed or a remote source
+    /// was refreshed.
+    fn reload_local(&self) -> Result<usize, ApplicationServiceError> {
+        let _reload_guard = self
+            .reload_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.reload_locked()
+    }
+
+    fn reload_locked(&self) -> Result<usize, ApplicationServiceError> {
+        let result = self.app.reload(self.application_service.as_ref());
+        match result {
+            Ok((bookmark_count, bookmark_files)) => {
+                log::info!("Reloaded {} bookmarks", bookmark_count);
+                if let Err(err) = self.watch(bookmark_files) {
+                    log::warn!("Could not watch bookmark files for changes: {}", err);
+                }
+                Ok(bookmark_count)
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                Err(ApplicationServiceError::Reload(err.to_string()))
+            }
+        }
+    }
+
+    /// Watches the configuration and the given bookmark files, unless they are watched already.
+    fn watch(&self, bookmark_files: Vec<PathBuf>) -> Result<(), NotifyFileWatcherError> {
+        let mut watched_files = self
+            .watched_files
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(watched_files) = watched_files.as_ref() {
+            if watched_files.files == bookmark_files {
+                return Ok(());
+            }
+        }
+        *watched_files = Some(WatchedFiles {
+            _watcher: self.app.watch_bookmark_files(&bookmark_files)?,
+            files: bookmark_files,
+        });
+        Ok(())
+    }
+}
+
+/// Explicit reloads pull the git repository first, keeping the previous bookmarks when that fails.
+impl ReloadService for AppReloadService {
+    fn reload(&self) -> Result<usize, ApplicationServiceError> {
+        let _reload_guard = self
+            .reload_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(err) = self.app.pull() {
+            log::error!("{}", err);
+            return Err(ApplicationServiceError::Reload(err.to_string()));
+        }
+        self.reload_locked()
+    }
+}
+
+/// Changes are made while holding the reload lock, so they are not lost to a reload that started
+/// before them. The application service indexes each change, so nothing needs to be reloaded.
+impl BookmarkEditService for AppReloadService {
+    fn save_bookmark(
+        &self,
+        bookmark: Bookmark,
+        editor: Editor,
+    ) -> Result<BookmarkChange, ApplicationServiceError> {
+        let _reload_guard = self
+            .reload_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.application_service.save_bookmark(bookmark, editor)
+    }
+
+    fn remove_bookmark(
+        &self,
+        id: Uuid,
+        editor: Editor,
+    ) -> Result<Bookmark, ApplicationServiceError> {
+        let _reload_guard = self
+            .reload_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.application_service.remove_bookmark(id, editor)
     }
 }
 
@@ -83,14 +486,32 @@ impl AppInitialisationError {
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to reload bookmarks, continuing with previous bookmarks: {cause}")]
+pub(crate) struct AppReloadError {
+    cause: AppInitialisationCause,
+}
+
+impl From<AppInitialisationError> for AppReloadError {
+    fn from(err: AppInitialisationError) -> Self {
+        AppReloadError { cause: err.cause }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 enum AppInitialisationCause {
+    #[error(transparent)]
+    Config(#[from] AppConfigError),
     #[error(transparent)]
     Repository(#[from] BookmarkRepositoryError),
     #[error(transparent)]
-    FileSystemRepository(#[from] FileSystemBookmarkRepositoryAdapterError),
+    Visits(#[from] BookmarkVisitRepositoryError),
     #[error(transparent)]
     SearchEngine(#[from] TantivyBookmarkSearchEngineAdapterError),
+    #[error(transparent)]
+    FileWatch(#[from] NotifyFileWatcherError),
+    #[error(transparent)]
+    Server(#[from] warp::Error),
 }
 
 fn map_initialisation_error_cause<C: Into<AppInitialisationCause>>(
@@ -98,3 +519,98 @@ fn map_initialisation_error_cause<C: Into<AppInitialisationCause>>(
 ) -> AppInitialisationError {
     AppInitialisationError::new(cause.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    fn names(bookmarks: Vec<Bookmark>) -> Vec<String> {
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.name().clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn finishes_in_flight_requests_and_stops_listening_on_shutdown() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        let directory = tempfile::tempdir().unwrap();
+        let config_path = directory.path().join("config.yml");
+        std::fs::write(
+            &config_path,
+            "bookmarks: bookmarks.yml\nserver:\n  address: 127.0.0.1:0\n",
+        )
+        .unwrap();
+        std::fs::write(
+            directory.path().join("bookmarks.yml"),
+            "- name: Wiki\n  url: https://wiki.example.com\n",
+        )
+        .unwrap();
+        let app = App::new(config_path);
+        let shutdown = app.shutdown_trigger();
+        let (listening, address) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            app.serve(|address| {
+                let _ = listening.send(address);
+            })
+            .await
+            .is_ok()
+        });
+        let address = address.await.unwrap();
+        let mut connection = TcpStream::connect(address).await.unwrap();
+
+        connection
+            .write_all(
+                b"GET /suggestions?q=wiki HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        shutdown.trigger();
+        let mut response = String::new();
+        connection.read_to_string(&mut response).await.unwrap();
+        let stopped = tokio::time::timeout(std::time::Duration::from_secs(10), server).await;
+
+        assert_that(&response.starts_with("HTTP/1.1 200 OK")).is_true();
+        assert_that(&stopped.map(|joined| joined.unwrap())).is_equal_to(Ok(true));
+        assert_that(&TcpStream::connect(address).await.is_err()).is_true();
+    }
+
+    #[test]
+    fn keeps_the_previous_bookmarks_and_index_when_a_reload_fails() {
+        let directory = tempfile::tempdir().unwrap();
+        let config_path = directory.path().join("config.yml");
+        let bookmarks_path = directory.path().join("bookmarks.yml");
+        std::fs::write(
+            &config_path,
+            "bookmarks: bookmarks.yml\nsearch:\n  index_directory: index\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &bookmarks_path,
+            "- name: Wiki\n  url: https://wiki.example.com\n",
+        )
+        .unwrap();
+        let app = App::new(config_path.clone());
+        let config = app.config().unwrap();
+        let (bookmark_repository, bookmark_search_engine) = app.bookmarks(&config).unwrap();
+        let application_service = ApplicationServiceImpl::new(
+            bookmark_repository,
+            bookmark_search_engine,
+            SqliteBookmarkVisitRepositoryAdapter::open(None, None).unwrap(),
+        );
+
+        std::fs::write(&bookmarks_path, "- name: [Wiki\n").unwrap();
+        let reloaded = app.reload(&application_service);
+
+        assert_that(&reloaded.is_err()).is_true();
+        assert_that(&names(
+            application_service.search("wiki".to_string()).unwrap(),
+        ))
+        .is_equal_to(vec!["Wiki".to_string()]);
+        assert_that(&directory.path().join("index").join("tantivy").is_dir()).is_true();
+    }
+}
@@ -1,7 +1,15 @@
-use crate::domain::bookmark::BookmarkSearchEngineError;
+use crate::domain::bookmark::{
+    BookmarkRepositoryError, BookmarkSearchEngineError, BookmarkVisitRepositoryError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum ApplicationServiceError {
     #[error(transparent)]
     Search(#[from] BookmarkSearchEngineError),
+    #[error(transparent)]
+    Repository(#[from] BookmarkRepositoryError),
+    #[error(transparent)]
+    Visits(#[from] BookmarkVisitRepositoryError),
+    #[error("{0}")]
+    Reload(String),
 }
@@ -1,43 +1,180 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, SubsecRound, Utc};
 use url::Url;
+use uuid::Uuid;
 
 pub(crate) use error::*;
 
-use crate::domain::bookmark::BookmarkSearchEngine;
+use crate::domain::bookmark::{
+    Bookmark, BookmarkChange, BookmarkRepository, BookmarkRepositoryError, BookmarkSearchEngine,
+    BookmarkVisit, BookmarkVisitRepository, BookmarkVisitRepositoryError, BookmarkVisits, Editor,
+};
 
 mod error;
 
 pub(crate) trait ApplicationService {
-    fn search(&self, term: String) -> Result<Vec<Url>, ApplicationServiceError>;
+    fn search(&self, term: String) -> Result<Vec<Bookmark>, ApplicationServiceError>;
 
     fn suggest(&self, term: String) -> Result<Vec<String>, ApplicationServiceError>;
+
+    fn bookmarks(&self) -> Result<Vec<Bookmark>, ApplicationServiceError>;
+
+    /// Finds the url to send a visitor of a bookmark to, recording the visit and the query it came
+    /// from unless visit tracking is disabled.
+    fn visit(&self, id: Uuid, query: Option<String>) -> Result<Url, ApplicationServiceError>;
 }
 
-#[derive(Default)]
-pub(crate) struct ApplicationServiceImpl<BSE: BookmarkSearchEngine> {
+pub(crate) trait ReloadService {
+    fn reload(&self) -> Result<usize, ApplicationServiceError>;
+}
+
+pub(crate) trait BookmarkEditService {
+    fn save_bookmark(
+        &self,
+        bookmark: Bookmark,
+        editor: Editor,
+    ) -> Result<BookmarkChange, ApplicationServiceError>;
+
+    fn remove_bookmark(
+        &self,
+        id: Uuid,
+        editor: Editor,
+    ) -> Result<Bookmark, ApplicationServiceError>;
+}
+
+pub(crate) struct ApplicationServiceImpl<BR, BSE, BVR>
+where
+    BR: BookmarkRepository,
+    BSE: BookmarkSearchEngine,
+    BVR: BookmarkVisitRepository,
+{
+    bookmark_collection: RwLock<Arc<BookmarkCollection<BR, BSE>>>,
+    bookmark_visits: BVR,
+    visit_tracking: bool,
+}
+
+struct BookmarkCollection<BR, BSE> {
+    bookmark_repository: BR,
     bookmark_search_engine: BSE,
 }
 
-impl<BSE: BookmarkSearchEngine> ApplicationServiceImpl<BSE> {
-    pub(crate) fn new(bookmark_search_engine: BSE) -> Self {
+impl<BR, BSE, BVR> ApplicationServiceImpl<BR, BSE, BVR>
+where
+    BR: BookmarkRepository,
+    BSE: BookmarkSearchEngine,
+    BVR: BookmarkVisitRepository,
+{
+    pub(crate) fn new(
+        bookmark_repository: BR,
+        bookmark_search_engine: BSE,
+        bookmark_visits: BVR,
+    ) -> Self {
+        record_visits(&bookmark_visits, &bookmark_search_engine);
         ApplicationServiceImpl {
+            bookmark_collection: RwLock::new(Arc::new(BookmarkCollection {
+                bookmark_repository,
+                bookmark_search_engine,
+            })),
+            bookmark_visits,
+            visit_tracking: true,
+        }
+    }
+
+    pub(crate) fn with_visit_tracking(self, visit_tracking: bool) -> Self {
+        ApplicationServiceImpl {
+            visit_tracking,
+            ..self
+        }
+    }
+
+    pub(crate) fn replace_bookmarks(&self, bookmark_repository: BR, bookmark_search_engine: BSE) {
+        record_visits(&self.bookmark_visits, &bookmark_search_engine);
+        let mut current = self
+            .bookmark_collection
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *current = Arc::new(BookmarkCollection {
+            bookmark_repository,
             bookmark_search_engine,
+        });
+    }
+
+    fn bookmark_collection(&self) -> Arc<BookmarkCollection<BR, BSE>> {
+        self.bookmark_collection
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+/// Visit statistics are informational, so bookmarks are still returned without them when they
+/// cannot be read.
+fn with_visits(
+    bookmarks: Vec<Bookmark>,
+    visits: Result<HashMap<Uuid, BookmarkVisits>, BookmarkVisitRepositoryError>,
+) -> Vec<Bookmark> {
+    let visits = match visits {
+        Ok(visits) => visits,
+        Err(err) => {
+            log::warn!("{}", err);
+            return bookmarks;
         }
+    };
+    bookmarks
+        .into_iter()
+        .map(|bookmark| match visits.get(&bookmark.id()) {
+            Some(bookmark_visits) => bookmark.with_visits(bookmark_visits),
+            None => bookmark,
+        })
+        .collect()
+}
+
+/// Visits only affect ranking, so a search engine is still used without them when they cannot be
+/// read.
+fn record_visits<BVR, BSE>(bookmark_visits: &BVR, bookmark_search_engine: &BSE)
+where
+    BVR: BookmarkVisitRepository,
+    BSE: BookmarkSearchEngine,
+{
+    let recorded = bookmark_visits
+        .get_visits()
+        .map_err(ApplicationServiceError::from)
+        .and_then(|visits| {
+            bookmark_search_engine
+                .record_visits(&visits)
+                .map_err(ApplicationServiceError::from)
+        });
+    if let Err(err) = recorded {
+        log::warn!("Ranking without visits: {}", err);
     }
 }
 
-impl<BSE: BookmarkSearchEngine> ApplicationService for ApplicationServiceImpl<BSE> {
-    fn search(&self, term: String) -> Result<Vec<Url>, ApplicationServiceError> {
-        Ok(self
+/// Timestamps are kept to the second, as they end up in hand-edited bookmark files.
+fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(0)
+}
+
+impl<BR, BSE, BVR> ApplicationService for ApplicationServiceImpl<BR, BSE, BVR>
+where
+    BR: BookmarkRepository,
+    BSE: BookmarkSearchEngine,
+    BVR: BookmarkVisitRepository,
+{
+    fn search(&self, term: String) -> Result<Vec<Bookmark>, ApplicationServiceError> {
+        let bookmarks = self
+            .bookmark_collection()
             .bookmark_search_engine
             .search(term)
-            .map_err(ApplicationServiceError::from)?
-            .iter()
-            .map(|bookmark| bookmark.url().clone())
-            .collect())
+            .map_err(ApplicationServiceError::from)?;
+        let ids: Vec<Uuid> = bookmarks.iter().map(Bookmark::id).collect();
+        Ok(with_visits(bookmarks, self.bookmark_visits.get(&ids)))
     }
 
     fn suggest(&self, term: String) -> Result<Vec<String>, ApplicationServiceError> {
         Ok(self
+            .bookmark_collection()
             .bookmark_search_engine
             .search(term)
             .map_err(ApplicationServiceError::from)?
@@ -45,4 +182,80 @@ impl<BSE: BookmarkSearchEngine> ApplicationService for ApplicationServiceImpl<BS
             .map(|bookmark| bookmark.name().clone())
             .collect())
     }
+
+    fn bookmarks(&self) -> Result<Vec<Bookmark>, ApplicationServiceError> {
+        let bookmarks = self
+            .bookmark_collection()
+            .bookmark_repository
+            .get_all()
+            .map_err(ApplicationServiceError::from)?;
+        Ok(with_visits(bookmarks, self.bookmark_visits.get_all()))
+    }
+
+    fn visit(&self, id: Uuid, query: Option<String>) -> Result<Url, ApplicationServiceError> {
+        let bookmark_collection = self.bookmark_collection();
+        let bookmark = bookmark_collection.bookmark_repository.get(id)?;
+        if self.visit_tracking {
+            let visit = BookmarkVisit::new(id, now(), query);
+            if let Err(err) = self.bookmark_visits.record_visit(&visit) {
+                log::warn!("Could not record visit to {}: {}", bookmark.url(), err);
+            }
+            if let Err(err) = bookmark_collection
+                .bookmark_search_engine
+                .record_visits(&[visit])
+            {
+                log::warn!("Could not rank by visit to {}: {}", bookmark.url(), err);
+            }
+        }
+        Ok(bookmark.url().clone())
+    }
+}
+
+impl<BR, BSE, BVR> BookmarkEditService for ApplicationServiceImpl<BR, BSE, BVR>
+where
+    BR: BookmarkRepository,
+    BSE: BookmarkSearchEngine,
+    BVR: BookmarkVisitRepository,
+{
+    /// Stamps the bookmark as updated now, keeping the creation time of the bookmark it replaces,
+    /// and indexes it as saved so that it can be searched for straight away.
+    fn save_bookmark(
+        &self,
+        bookmark: Bookmark,
+        editor: Editor,
+    ) -> Result<BookmarkChange, ApplicationServiceError> {
+        let now = now();
+        let id = bookmark.id();
+        let bookmark_collection = self.bookmark_collection();
+        let created_at = match bookmark_collection.bookmark_repository.get(id) {
+            Ok(existing) => existing.created_at(),
+            Err(BookmarkRepositoryError::BookmarkNotFound(_)) => Some(now),
+            Err(err) => return Err(err.into()),
+        };
+        let change = bookmark_collection.bookmark_repository.save(
+            &bookmark
+                .with_created_at(created_at)
+                .with_updated_at(Some(now)),
+            &editor,
+        )?;
+        let saved = bookmark_collection.bookmark_repository.get(id)?;
+        match change {
+            BookmarkChange::Added => bookmark_collection.bookmark_search_engine.index(&saved)?,
+            BookmarkChange::Updated => bookmark_collection.bookmark_search_engine.update(&saved)?,
+        }
+        Ok(change)
+    }
+
+    fn remove_bookmark(
+        &self,
+        id: Uuid,
+        editor: Editor,
+    ) -> Result<Bookmark, ApplicationServiceError> {
+        let bookmark_collection = self.bookmark_collection();
+        let bookmark = bookmark_collection
+            .bookmark_repository
+            .remove(id, &editor)?;
+        bookmark_collection.bookmark_search_engine.remove(id)?;
+        Ok(bookmark)
+    }
 }
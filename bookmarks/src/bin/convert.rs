@@ -0,0 +1,73 @@
+use bookmarks::{
+    convert_bookmarks_to_xbel, convert_bookmarks_to_yaml, export_bookmarks_to_csv,
+    export_bookmarks_to_html,
+};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+const USAGE: &str =
+    "Usage: convert <input> [output.yml | output.xbel | output.html | output.csv] [--primary-tag <tag>]...";
+
+fn main() {
+    env_logger::init();
+    let mut paths: Vec<PathBuf> = vec![];
+    let mut primary_tags: Vec<String> = vec![];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--primary-tag" {
+            match args.next() {
+                Some(tag) => primary_tags.push(tag),
+                None => usage(),
+            }
+        } else {
+            paths.push(PathBuf::from(arg));
+        }
+    }
+    let (input, output) = match paths.as_slice() {
+        [input] => (input, None),
+        [input, output] => (input, Some(output)),
+        _ => usage(),
+    };
+
+    let converted = if output.is_some_and(|output| has_extension(output, &["html", "htm"])) {
+        export_bookmarks_to_html(input, primary_tags)
+    } else if output.is_some_and(|output| has_extension(output, &["csv"])) {
+        export_bookmarks_to_csv(input)
+    } else if output.is_some_and(|output| has_extension(output, &["xbel"])) {
+        convert_bookmarks_to_xbel(input)
+    } else {
+        convert_bookmarks_to_yaml(input)
+    };
+    let converted = match converted {
+        Ok(converted) => converted,
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(1)
+        }
+    };
+
+    match output {
+        Some(output) => {
+            if let Err(err) = std::fs::write(output, converted) {
+                eprintln!("Could not write {}: {}", output.display(), err);
+                exit(1)
+            }
+        }
+        None => print!("{}", converted),
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extensions
+                .iter()
+                .any(|expected| extension.eq_ignore_ascii_case(expected))
+        })
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    exit(2)
+}
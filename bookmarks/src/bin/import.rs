@@ -0,0 +1,66 @@
+use bookmarks::{import_bookmarks, ImportFormat};
+use std::path::PathBuf;
+use std::process::exit;
+
+const USAGE: &str =
+    "Usage: import <config> <import file> --format <netscape|chromium|firefox|xbel|csv|pinboard|linkding> [--into <bookmarks.yml>]";
+
+fn main() {
+    env_logger::init();
+    let mut paths: Vec<PathBuf> = vec![];
+    let mut format: Option<ImportFormat> = None;
+    let mut target_file: Option<PathBuf> = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().map(|format| format.parse()) {
+                Some(Ok(parsed)) => format = Some(parsed),
+                Some(Err(err)) => fail(err),
+                None => usage(),
+            },
+            "--into" => match args.next() {
+                Some(path) => target_file = Some(PathBuf::from(path)),
+                None => usage(),
+            },
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let (config_path, import_file, format) = match (paths.as_slice(), format) {
+        ([config_path, import_file], Some(format)) => (config_path, import_file, format),
+        _ => usage(),
+    };
+
+    let report = match import_bookmarks(config_path, import_file, format, target_file) {
+        Ok(report) => report,
+        Err(err) => fail(err),
+    };
+    report
+        .added()
+        .iter()
+        .for_each(|url| println!("Added   {}", url));
+    report
+        .skipped()
+        .iter()
+        .for_each(|url| println!("Skipped {}", url));
+    report
+        .invalid()
+        .iter()
+        .for_each(|row| println!("Invalid {}", row));
+    println!(
+        "Added {}, skipped {} and rejected {} bookmarks in {}",
+        report.added().len(),
+        report.skipped().len(),
+        report.invalid().len(),
+        report.target_file().display()
+    );
+}
+
+fn fail<E: std::fmt::Display>(err: E) -> ! {
+    eprintln!("{}", err);
+    exit(1)
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    exit(2)
+}
@@ -1,14 +1,35 @@
 use bookmarks::App;
 use std::path::PathBuf;
 use std::process::exit;
+use tokio::signal::unix::{signal, SignalKind};
 
 #[tokio::main]
 async fn main() {
     env_logger::init();
     let args: Vec<String> = std::env::args().collect();
     let search_engine_config_path = PathBuf::from(&args[1]);
+    let app = App::new(search_engine_config_path);
 
-    if let Err(err) = App::new(search_engine_config_path).run().await {
+    let reload_trigger = app.reload_trigger();
+    let mut hangups = signal(SignalKind::hangup()).expect("Could not listen for SIGHUP");
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            reload_trigger.trigger();
+        }
+    });
+
+    let shutdown_trigger = app.shutdown_trigger();
+    let mut terminations = signal(SignalKind::terminate()).expect("Could not listen for SIGTERM");
+    let mut interrupts = signal(SignalKind::interrupt()).expect("Could not listen for SIGINT");
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = terminations.recv() => {},
+            _ = interrupts.recv() => {},
+        }
+        shutdown_trigger.trigger();
+    });
+
+    if let Err(err) = app.run().await {
         eprintln!("{}", err);
         exit(1)
     }
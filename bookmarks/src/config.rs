@@ -0,0 +1,423 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::domain::synonyms::Synonyms;
+use crate::ports::export::ExportConfig;
+use crate::ports::http::warp::AdminUser;
+use crate::ports::persistence::file_system::{BookmarkFileFormat, BookmarkSource, CsvColumns};
+use crate::ports::persistence::RepositoryConfig;
+use crate::ports::search::tantivy::{FrecencyConfig, TextAnalysisConfig};
+
+const DEFAULT_VISITS_DATABASE: &str = "visits.db";
+
+#[derive(Debug)]
+pub(crate) struct AppConfig {
+    bookmark_sources: Vec<BookmarkSource>,
+    search: SearchConfig,
+    synonyms: Synonyms,
+    admin: AdminConfig,
+    server: ServerConfig,
+    export: ExportConfig,
+    csv: CsvColumns,
+    repository: RepositoryConfig,
+    visits: VisitsConfig,
+}
+
+impl AppConfig {
+    pub(crate) fn load<P: AsRef<Path>>(config_path: P) -> Result<Self, AppConfigError> {
+        let content = std::fs::read(config_path.as_ref()).map_err(|_| AppConfigError::ReadError)?;
+        Self::from_slice(content.as_slice(), config_path.as_ref())
+    }
+
+    fn from_slice(content: &[u8], config_path: &Path) -> Result<Self, AppConfigError> {
+        let value: serde_yaml::Value = match BookmarkFileFormat::detect(config_path) {
+            BookmarkFileFormat::Html | BookmarkFileFormat::Xbel | BookmarkFileFormat::Markdown => {
+                return Ok(Self::bookmarks_only(config_path))
+            }
+            BookmarkFileFormat::Json => serde_json::from_slice(content)
+                .map_err(|err| AppConfigError::DeserializeError(err.to_string()))?,
+            BookmarkFileFormat::Toml => toml::from_slice(content)
+                .map_err(|err| AppConfigError::DeserializeError(err.to_string()))?,
+            BookmarkFileFormat::Yaml => serde_yaml::from_slice(content)
+                .map_err(|err| AppConfigError::DeserializeError(err.to_string()))?,
+        };
+
+        if is_bookmark_file(&value) {
+            return Ok(Self::bookmarks_only(config_path));
+        }
+
+        let file: AppConfigFile = serde_yaml::from_value(value)
+            .map_err(|err| AppConfigError::DeserializeError(err.to_string()))?;
+        let config_directory = config_path.parent().unwrap_or_else(|| Path::new(""));
+
+        Ok(AppConfig {
+            bookmark_sources: file
+                .bookmarks
+                .into_vec()
+                .into_iter()
+                .map(|source| source.resolve_path(config_directory))
+                .collect(),
+            search: file.search.resolve_paths(config_directory),
+            synonyms: file.synonyms,
+            admin: file.admin,
+            server: file.server,
+            export: file.export,
+            csv: file.csv,
+            repository: file.repository.resolve_paths(config_directory),
+            visits: file.visits.resolve_paths(config_directory),
+        })
+    }
+
+    fn bookmarks_only(bookmarks_path: &Path) -> Self {
+        let config_directory = bookmarks_path.parent().unwrap_or_else(|| Path::new(""));
+        AppConfig {
+            bookmark_sources: vec![BookmarkSource::new(bookmarks_path, vec![])],
+            search: SearchConfig::default(),
+            synonyms: Synonyms::default(),
+            admin: AdminConfig::default(),
+            server: ServerConfig::default(),
+            export: ExportConfig::default(),
+            csv: CsvColumns::default(),
+            repository: RepositoryConfig::default(),
+            visits: VisitsConfig::default().resolve_paths(config_directory),
+        }
+    }
+
+    pub(crate) fn bookmark_sources(&self) -> &Vec<BookmarkSource> {
+        &self.bookmark_sources
+    }
+
+    pub(crate) fn search(&self) -> &SearchConfig {
+        &self.search
+    }
+
+    pub(crate) fn synonyms(&self) -> &Synonyms {
+        &self.synonyms
+    }
+
+    pub(crate) fn admin(&self) -> &AdminConfig {
+        &self.admin
+    }
+
+    pub(crate) fn server(&self) -> &ServerConfig {
+        &self.server
+    }
+
+    pub(crate) fn export(&self) -> &ExportConfig {
+        &self.export
+    }
+
+    pub(crate) fn csv(&self) -> &CsvColumns {
+        &self.csv
+    }
+
+    pub(crate) fn repository(&self) -> &RepositoryConfig {
+        &self.repository
+    }
+
+    pub(crate) fn visits(&self) -> &VisitsConfig {
+        &self.visits
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct SearchConfig {
+    text_analysis: TextAnalysisConfig,
+    index_directory: Option<PathBuf>,
+    frecency: FrecencyConfig,
+}
+
+impl SearchConfig {
+    fn resolve_paths(self, config_directory: &Path) -> Self {
+        SearchConfig {
+            index_directory: self
+                .index_directory
+                .map(|index_directory| config_directory.join(index_directory)),
+            ..self
+        }
+    }
+
+    pub(crate) fn index_directory(&self) -> Option<&Path> {
+        self.index_directory.as_deref()
+    }
+
+    pub(crate) fn text_analysis(&self) -> &TextAnalysisConfig {
+        &self.text_analysis
+    }
+
+    pub(crate) fn frecency(&self) -> &FrecencyConfig {
+        &self.frecency
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct VisitsConfig {
+    enabled: bool,
+    path: Option<PathBuf>,
+    retention_days: Option<u32>,
+}
+
+impl VisitsConfig {
+    /// Tracked visits are kept in a database next to the configuration unless another one is named.
+    fn resolve_paths(self, config_directory: &Path) -> Self {
+        let enabled = self.enabled;
+        let path = self
+            .path
+            .or_else(|| Some(PathBuf::from(DEFAULT_VISITS_DATABASE)).filter(|_| enabled));
+        VisitsConfig {
+            path: path.map(|path| config_directory.join(path)),
+            ..self
+        }
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The database visits are recorded in, or `None` when tracking is off and none was named.
+    pub(crate) fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// How long individual visits are kept, or `None` to keep them forever.
+    pub(crate) fn retention(&self) -> Option<chrono::Duration> {
+        self.retention_days
+            .map(|retention_days| chrono::Duration::days(retention_days.into()))
+    }
+}
+
+impl Default for VisitsConfig {
+    fn default() -> Self {
+        VisitsConfig {
+            enabled: true,
+            path: None,
+            retention_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct AdminConfig {
+    token: Option<String>,
+    users: Vec<AdminUser>,
+}
+
+impl AdminConfig {
+    /// The configured users, with the shared `token` authenticating as `admin`.
+    pub(crate) fn users(&self) -> Vec<AdminUser> {
+        let mut users = self.users.clone();
+        if let Some(token) = &self.token {
+            users.push(AdminUser::new("admin".to_string(), None, token.clone()));
+        }
+        users
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct ServerConfig {
+    address: SocketAddr,
+    shutdown_timeout_seconds: u64,
+}
+
+impl ServerConfig {
+    pub(crate) fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub(crate) fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_seconds)
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            address: SocketAddr::from(([127, 0, 0, 1], 3033)),
+            shutdown_timeout_seconds: 30,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum BookmarkSources {
+    One(BookmarkSource),
+    Many(Vec<BookmarkSource>),
+}
+
+impl Default for BookmarkSources {
+    fn default() -> Self {
+        BookmarkSources::Many(vec![])
+    }
+}
+
+impl BookmarkSources {
+    fn into_vec(self) -> Vec<BookmarkSource> {
+        match self {
+            BookmarkSources::One(source) => vec![source],
+            BookmarkSources::Many(sources) => sources,
+        }
+    }
+}
+
+fn is_bookmark_file(value: &serde_yaml::Value) -> bool {
+    if value.is_sequence() {
+        return true;
+    }
+    let include = serde_yaml::Value::from("include");
+    let bookmarks = serde_yaml::Value::from("bookmarks");
+    let url = serde_yaml::Value::from("url");
+    let name = serde_yaml::Value::from("name");
+    let group = serde_yaml::Value::from("group");
+
+    value.get(&include).is_some()
+        || value
+            .get(&bookmarks)
+            .and_then(|bookmarks| bookmarks.as_sequence())
+            .is_some_and(|entries| {
+                entries.iter().any(|entry| {
+                    entry.as_mapping().is_some_and(|entry| {
+                        (entry.contains_key(&url) && entry.contains_key(&name))
+                            || entry.contains_key(&group)
+                    })
+                })
+            })
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AppConfigFile {
+    #[serde(default)]
+    bookmarks: BookmarkSources,
+    #[serde(default)]
+    search: SearchConfig,
+    #[serde(default)]
+    synonyms: Synonyms,
+    #[serde(default)]
+    admin: AdminConfig,
+    #[serde(default)]
+    server: ServerConfig,
+    #[serde(default)]
+    export: ExportConfig,
+    #[serde(default)]
+    csv: CsvColumns,
+    #[serde(default)]
+    repository: RepositoryConfig,
+    #[serde(default)]
+    visits: VisitsConfig,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum AppConfigError {
+    #[error("Could not read configuration file")]
+    ReadError,
+    #[error("Could not deserialize configuration: {0}")]
+    DeserializeError(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn treats_a_list_of_bookmarks_as_bookmarks_only_configuration() {
+        let content = "- name: GitHub\n  url: https://github.com\n  tags: []\n";
+
+        let config =
+            AppConfig::from_slice(content.as_bytes(), Path::new("conf/bookmarks.yml")).unwrap();
+
+        assert_that(&config.bookmark_sources()[0].path())
+            .is_equal_to(Path::new("conf/bookmarks.yml"));
+    }
+
+    #[test]
+    fn resolves_bookmarks_path_relative_to_configuration_file() {
+        let content =
+            "bookmarks: bookmarks.yml\nsearch:\n  text_analysis:\n    language: english\n";
+
+        let config =
+            AppConfig::from_slice(content.as_bytes(), Path::new("conf/server.yml")).unwrap();
+
+        assert_that(&config.bookmark_sources()[0].path())
+            .is_equal_to(Path::new("conf/bookmarks.yml"));
+    }
+
+    #[test]
+    fn treats_a_bookmark_file_with_default_tags_as_bookmarks_only_configuration() {
+        let content = "tags: [platform]\nbookmarks:\n  - name: GitHub\n    url: https://github.com\n    tags: []\n";
+
+        let config =
+            AppConfig::from_slice(content.as_bytes(), Path::new("conf/platform.yml")).unwrap();
+
+        assert_that(&config.bookmark_sources()[0].path())
+            .is_equal_to(Path::new("conf/platform.yml"));
+    }
+
+    #[test]
+    fn reads_toml_configuration_with_an_explicit_bookmark_format() {
+        let content = "[[bookmarks]]\npath = \"generated.txt\"\nformat = \"json\"\n\n[server]\n";
+
+        let config =
+            AppConfig::from_slice(content.as_bytes(), Path::new("conf/server.toml")).unwrap();
+
+        assert_that(&config.bookmark_sources()[0].path())
+            .is_equal_to(Path::new("conf/generated.txt"));
+        assert_that(&config.bookmark_sources()[0].format())
+            .is_equal_to(Some(BookmarkFileFormat::Json));
+    }
+
+    #[test]
+    fn reads_a_remote_bookmark_source_with_a_cache_relative_to_configuration_file() {
+        let content = "bookmarks:\n  - url: https://example.com/bookmarks.yml\n    cache: cache/shared.yml\n    poll_interval_seconds: 60\n";
+
+        let config =
+            AppConfig::from_slice(content.as_bytes(), Path::new("conf/server.yml")).unwrap();
+
+        let source = &config.bookmark_sources()[0];
+        assert_that(&source.path()).is_equal_to(Path::new("conf/cache/shared.yml"));
+        assert_that(&source.remote_file().map(|remote| remote.url().as_str()))
+            .is_equal_to(Some("https://example.com/bookmarks.yml"));
+    }
+
+    #[test]
+    fn listens_on_the_configured_address() {
+        let default = AppConfig::from_slice(
+            "bookmarks: bookmarks.yml\n".as_bytes(),
+            Path::new("conf/server.yml"),
+        )
+        .unwrap();
+        let configured = AppConfig::from_slice(
+            "bookmarks: bookmarks.yml\nserver:\n  address: 0.0.0.0:8080\n".as_bytes(),
+            Path::new("conf/server.yml"),
+        )
+        .unwrap();
+
+        assert_that(&default.server().address().to_string())
+            .is_equal_to("127.0.0.1:3033".to_string());
+        assert_that(&configured.server().address().to_string())
+            .is_equal_to("0.0.0.0:8080".to_string());
+    }
+
+    #[test]
+    fn keeps_tracked_visits_next_to_the_configuration_file_by_default() {
+        let tracked = AppConfig::from_slice(
+            "bookmarks: bookmarks.yml\n".as_bytes(),
+            Path::new("conf/server.yml"),
+        )
+        .unwrap();
+        let untracked = AppConfig::from_slice(
+            "bookmarks: bookmarks.yml\nvisits:\n  enabled: false\n".as_bytes(),
+            Path::new("conf/server.yml"),
+        )
+        .unwrap();
+
+        assert_that(&tracked.visits().path()).is_equal_to(Some(Path::new("conf/visits.db")));
+        assert_that(&untracked.visits().path()).is_none();
+    }
+}
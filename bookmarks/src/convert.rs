@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use crate::config::{AppConfig, AppConfigError};
+use crate::domain::bookmark::{Bookmark, BookmarkRepository, BookmarkRepositoryError};
+use crate::ports::export::{bookmarks_csv, netscape_html, ExportConfig};
+use crate::ports::persistence::file_system::{
+    convert_to_xbel, convert_to_yaml, FileSystemBookmarkRepositoryAdapterError,
+};
+use crate::ports::persistence::ConfiguredBookmarkRepository;
+
+pub fn convert_bookmarks_to_yaml<P: AsRef<Path>>(input: P) -> Result<String, ConvertError> {
+    convert_to_yaml(input.as_ref()).map_err(map_convert_error_cause)
+}
+
+pub fn convert_bookmarks_to_xbel<P: AsRef<Path>>(input: P) -> Result<String, ConvertError> {
+    convert_to_xbel(input.as_ref()).map_err(map_convert_error_cause)
+}
+
+pub fn export_bookmarks_to_html<P: AsRef<Path>>(
+    input: P,
+    primary_tags: Vec<String>,
+) -> Result<String, ConvertError> {
+    let config = AppConfig::load(input.as_ref()).map_err(map_convert_error_cause)?;
+    let bookmarks = load_bookmarks(&config)?;
+    let export_config = if primary_tags.is_empty() {
+        config.export().clone()
+    } else {
+        ExportConfig::new(primary_tags)
+    };
+
+    Ok(netscape_html(&bookmarks, &export_config))
+}
+
+pub fn export_bookmarks_to_csv<P: AsRef<Path>>(input: P) -> Result<String, ConvertError> {
+    let config = AppConfig::load(input.as_ref()).map_err(map_convert_error_cause)?;
+    let bookmarks = load_bookmarks(&config)?;
+
+    bookmarks_csv(&bookmarks, config.csv()).map_err(map_convert_error_cause)
+}
+
+fn load_bookmarks(config: &AppConfig) -> Result<Vec<Bookmark>, ConvertError> {
+    let bookmark_repository = ConfiguredBookmarkRepository::load(
+        config.repository(),
+        config.bookmark_sources().clone(),
+        config.synonyms().clone(),
+    )
+    .map_err(map_convert_error_cause)?;
+    bookmark_repository
+        .get_all()
+        .map_err(map_convert_error_cause)
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Could not convert bookmarks: {cause}")]
+pub struct ConvertError {
+    cause: ConvertErrorCause,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum ConvertErrorCause {
+    #[error(transparent)]
+    Config(#[from] AppConfigError),
+    #[error(transparent)]
+    Repository(#[from] BookmarkRepositoryError),
+    #[error(transparent)]
+    FileSystemRepository(#[from] FileSystemBookmarkRepositoryAdapterError),
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+}
+
+fn map_convert_error_cause<C: Into<ConvertErrorCause>>(cause: C) -> ConvertError {
+    ConvertError {
+        cause: cause.into(),
+    }
+}
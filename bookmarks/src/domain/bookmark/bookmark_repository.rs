@@ -6,12 +6,50 @@ pub(crate) trait BookmarkRepository {
     fn get_all(&self) -> Result<Vec<Bookmark>, BookmarkRepositoryError>;
 
     fn get(&self, id: Uuid) -> Result<Bookmark, BookmarkRepositoryError>;
+
+    fn save(
+        &self,
+        bookmark: &Bookmark,
+        editor: &Editor,
+    ) -> Result<BookmarkChange, BookmarkRepositoryError>;
+
+    fn remove(&self, id: Uuid, editor: &Editor) -> Result<Bookmark, BookmarkRepositoryError>;
+}
+
+/// The authenticated user making a change to the bookmark collection.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Editor {
+    name: String,
+    email: Option<String>,
+}
+
+impl Editor {
+    pub(crate) fn new(name: String, email: Option<String>) -> Self {
+        Editor { name, email }
+    }
+
+    pub(crate) fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub(crate) fn email(&self) -> Option<&String> {
+        self.email.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BookmarkChange {
+    Added,
+    Updated,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum BookmarkRepositoryError {
     #[error("Could not find Bookmark with id: {0}")]
     BookmarkNotFound(Uuid),
+    #[error("Bookmarks cannot be changed: {0}")]
+    ReadOnly(String),
     #[error("Unexpected persistence error occurred: {0}")]
     Unexpected(String),
 }
@@ -1,9 +1,18 @@
 use uuid::Uuid;
 
-use crate::domain::bookmark::Bookmark;
+use crate::domain::bookmark::{Bookmark, BookmarkVisit};
 
 pub(crate) trait BookmarkSearchEngine {
     fn search(&self, term: String) -> Result<Vec<Bookmark>, BookmarkSearchEngineError>;
+
+    fn index(&self, bookmark: &Bookmark) -> Result<(), BookmarkSearchEngineError>;
+
+    fn update(&self, bookmark: &Bookmark) -> Result<(), BookmarkSearchEngineError>;
+
+    fn remove(&self, id: Uuid) -> Result<(), BookmarkSearchEngineError>;
+
+    /// Lets visits to bookmarks count towards how they are ranked.
+    fn record_visits(&self, visits: &[BookmarkVisit]) -> Result<(), BookmarkSearchEngineError>;
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -13,5 +22,5 @@ pub(crate) enum BookmarkSearchEngineError {
     #[error("Could not find Bookmark with id: {0}")]
     BookmarkNotFound(Uuid),
     #[error("Unexpected search engine error occurred: {0}")]
-    Unexpected(String)
+    Unexpected(String),
 }
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Keeps track of how often and how recently bookmarks are opened, apart from the bookmarks
+/// themselves so that visits never rewrite bookmark files.
+pub(crate) trait BookmarkVisitRepository {
+    fn record_visit(&self, visit: &BookmarkVisit) -> Result<(), BookmarkVisitRepositoryError>;
+
+    fn get_all(&self) -> Result<HashMap<Uuid, BookmarkVisits>, BookmarkVisitRepositoryError>;
+
+    /// The visit counts of the given bookmarks, leaving out those that were never visited.
+    fn get(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, BookmarkVisits>, BookmarkVisitRepositoryError>;
+
+    /// The individual visits that are still kept, oldest first.
+    fn get_visits(&self) -> Result<Vec<BookmarkVisit>, BookmarkVisitRepositoryError>;
+}
+
+/// A single click-through to a bookmark, with the search it was found by.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BookmarkVisit {
+    bookmark_id: Uuid,
+    visited_at: DateTime<Utc>,
+    query: Option<String>,
+}
+
+impl BookmarkVisit {
+    pub(crate) fn new(bookmark_id: Uuid, visited_at: DateTime<Utc>, query: Option<String>) -> Self {
+        BookmarkVisit {
+            bookmark_id,
+            visited_at,
+            query,
+        }
+    }
+
+    pub(crate) fn bookmark_id(&self) -> Uuid {
+        self.bookmark_id
+    }
+
+    pub(crate) fn visited_at(&self) -> DateTime<Utc> {
+        self.visited_at
+    }
+
+    pub(crate) fn query(&self) -> Option<&String> {
+        self.query.as_ref()
+    }
+}
+
+/// The visits of one bookmark, counted over its whole lifetime.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct BookmarkVisits {
+    count: u64,
+    last_visited_at: Option<DateTime<Utc>>,
+}
+
+impl BookmarkVisits {
+    pub(crate) fn new(count: u64, last_visited_at: Option<DateTime<Utc>>) -> Self {
+        BookmarkVisits {
+            count,
+            last_visited_at,
+        }
+    }
+
+    pub(crate) fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub(crate) fn last_visited_at(&self) -> Option<DateTime<Utc>> {
+        self.last_visited_at
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum BookmarkVisitRepositoryError {
+    #[error("Unexpected visit persistence error occurred: {0}")]
+    Unexpected(String),
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+/// Combines how often and how recently bookmarks were visited into a single score, where every
+/// visit counts as one and is worth half as much after each half-life.
+#[derive(Debug, Clone)]
+pub(crate) struct Frecency {
+    half_life: Duration,
+    scores: HashMap<Uuid, DecayedScore>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DecayedScore {
+    value: f64,
+    at: DateTime<Utc>,
+}
+
+impl Frecency {
+    pub(crate) fn new(half_life: Duration) -> Self {
+        Frecency {
+            half_life,
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Visits can be added in any order, the score is kept as of the latest one.
+    pub(crate) fn add_visit(&mut self, bookmark_id: Uuid, visited_at: DateTime<Utc>) {
+        let half_life = self.half_life;
+        let score = self.scores.entry(bookmark_id).or_insert(DecayedScore {
+            value: 0.0,
+            at: visited_at,
+        });
+        if visited_at >= score.at {
+            score.value = score.value * decay(half_life, visited_at - score.at) + 1.0;
+            score.at = visited_at;
+        } else {
+            score.value += decay(half_life, score.at - visited_at);
+        }
+    }
+
+    pub(crate) fn score(&self, bookmark_id: Uuid, now: DateTime<Utc>) -> f64 {
+        self.scores
+            .get(&bookmark_id)
+            .map(|score| score.value * decay(self.half_life, now - score.at))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn scores(&self, now: DateTime<Utc>) -> HashMap<Uuid, f64> {
+        self.scores
+            .keys()
+            .map(|bookmark_id| (*bookmark_id, self.score(*bookmark_id, now)))
+            .collect()
+    }
+}
+
+fn decay(half_life: Duration, elapsed: Duration) -> f64 {
+    if elapsed <= Duration::zero() {
+        return 1.0;
+    }
+    if half_life <= Duration::zero() {
+        return 0.0;
+    }
+    0.5f64.powf(elapsed.num_seconds() as f64 / half_life.num_seconds() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn halves_the_worth_of_visits_every_half_life() {
+        let visited_at = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let bookmark_id = Uuid::new_v4();
+        let mut frecency = Frecency::new(Duration::days(30));
+
+        frecency.add_visit(bookmark_id, visited_at);
+        frecency.add_visit(bookmark_id, visited_at - Duration::days(30));
+
+        assert_that(&frecency.score(bookmark_id, visited_at)).is_equal_to(1.5);
+        assert_that(&frecency.score(bookmark_id, visited_at + Duration::days(60)))
+            .is_equal_to(0.375);
+        assert_that(&frecency.score(Uuid::new_v4(), visited_at)).is_equal_to(0.0);
+    }
+}
@@ -1,21 +1,40 @@
+use chrono::{DateTime, Utc};
 use url::Url;
 use uuid::Uuid;
 
 pub(crate) use bookmark_repository::*;
 pub(crate) use bookmark_search_engine::*;
+pub(crate) use bookmark_visit_repository::*;
+pub(crate) use frecency::*;
+pub(crate) use url_normalisation::*;
 
 mod bookmark_repository;
 mod bookmark_search_engine;
+mod bookmark_visit_repository;
+mod frecency;
+mod url_normalisation;
 
+#[derive(Debug, Clone)]
 pub(crate) struct Bookmark {
     id: Uuid,
     url: Url,
     name: String,
     description: Option<String>,
     tags: Vec<String>,
+    aliases: Vec<String>,
+    group_path: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    last_visited_at: Option<DateTime<Utc>>,
+    visit_count: u64,
 }
 
 impl Bookmark {
+    /// Bookmarks are identified by their url, so the same link always gets the same id.
+    pub(crate) fn id_for(url: &Url) -> Uuid {
+        Uuid::new_v5(&Uuid::NAMESPACE_URL, url.as_str().as_bytes())
+    }
+
     pub(crate) fn new(
         id: Uuid,
         url: Url,
@@ -29,6 +48,36 @@ impl Bookmark {
             name,
             description,
             tags,
+            aliases: vec![],
+            group_path: vec![],
+            created_at: None,
+            updated_at: None,
+            last_visited_at: None,
+            visit_count: 0,
+        }
+    }
+
+    pub(crate) fn with_aliases(self, aliases: Vec<String>) -> Self {
+        Bookmark { aliases, ..self }
+    }
+
+    pub(crate) fn with_group_path(self, group_path: Vec<String>) -> Self {
+        Bookmark { group_path, ..self }
+    }
+
+    pub(crate) fn with_created_at(self, created_at: Option<DateTime<Utc>>) -> Self {
+        Bookmark { created_at, ..self }
+    }
+
+    pub(crate) fn with_updated_at(self, updated_at: Option<DateTime<Utc>>) -> Self {
+        Bookmark { updated_at, ..self }
+    }
+
+    pub(crate) fn with_visits(self, visits: &BookmarkVisits) -> Self {
+        Bookmark {
+            last_visited_at: visits.last_visited_at(),
+            visit_count: visits.count(),
+            ..self
         }
     }
 
@@ -51,4 +100,28 @@ impl Bookmark {
     pub(crate) fn tags(&self) -> &Vec<String> {
         &self.tags
     }
+
+    pub(crate) fn aliases(&self) -> &Vec<String> {
+        &self.aliases
+    }
+
+    pub(crate) fn group_path(&self) -> &Vec<String> {
+        &self.group_path
+    }
+
+    pub(crate) fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub(crate) fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    pub(crate) fn last_visited_at(&self) -> Option<DateTime<Utc>> {
+        self.last_visited_at
+    }
+
+    pub(crate) fn visit_count(&self) -> u64 {
+        self.visit_count
+    }
 }
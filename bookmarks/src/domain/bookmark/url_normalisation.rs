@@ -0,0 +1,33 @@
+use url::Url;
+
+pub(crate) fn normalise_url(url: &Url) -> String {
+    let mut normalised = url.clone();
+    normalised.set_fragment(None);
+    if let Some(host) = url.host_str() {
+        if let Some(host) = host.strip_prefix("www.") {
+            let _ = normalised.set_host(Some(host));
+        }
+    }
+    if normalised.query() == Some("") {
+        normalised.set_query(None);
+    }
+
+    let path = normalised.path().trim_end_matches('/').to_string();
+    normalised.set_path(&path);
+    normalised.to_string().trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn ignores_case_www_prefix_trailing_slash_and_fragment() {
+        let a = Url::parse("HTTPS://www.Example.com/docs/?page=1#intro").unwrap();
+        let b = Url::parse("https://example.com/docs?page=1").unwrap();
+
+        assert_that(&normalise_url(&a)).is_equal_to(normalise_url(&b));
+    }
+}
@@ -1 +1,2 @@
 pub(crate) mod bookmark;
+pub(crate) mod synonyms;
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(from = "Vec<Vec<String>>")]
+pub(crate) struct Synonyms {
+    groups: Vec<Vec<String>>,
+    group_by_term: HashMap<String, usize>,
+}
+
+impl Synonyms {
+    pub(crate) fn new(groups: Vec<Vec<String>>) -> Self {
+        let groups: Vec<Vec<String>> = groups
+            .into_iter()
+            .filter(|group| !group.is_empty())
+            .collect();
+        let group_by_term = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| group.iter().map(move |term| (term.to_lowercase(), index)))
+            .collect();
+
+        Synonyms {
+            groups,
+            group_by_term,
+        }
+    }
+
+    pub(crate) fn canonical(&self, term: &str) -> Option<&String> {
+        self.group(term).and_then(|group| group.first())
+    }
+
+    pub(crate) fn variants(&self, term: &str) -> Option<&Vec<String>> {
+        self.group(term)
+    }
+
+    pub(crate) fn normalise_tags(&self, tags: &[String]) -> Vec<String> {
+        let mut normalised: Vec<String> = vec![];
+        tags.iter()
+            .map(|tag| self.canonical(tag).unwrap_or(tag))
+            .for_each(|tag| {
+                if !normalised.contains(tag) {
+                    normalised.push(tag.clone());
+                }
+            });
+        normalised
+    }
+
+    fn group(&self, term: &str) -> Option<&Vec<String>> {
+        self.group_by_term
+            .get(&term.to_lowercase())
+            .map(|index| &self.groups[*index])
+    }
+}
+
+impl From<Vec<Vec<String>>> for Synonyms {
+    fn from(groups: Vec<Vec<String>>) -> Self {
+        Synonyms::new(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    fn synonyms() -> Synonyms {
+        Synonyms::new(vec![vec!["kubernetes".to_string(), "k8s".to_string()]])
+    }
+
+    #[test]
+    fn resolves_any_variant_to_the_first_term_of_its_group() {
+        assert_that(&synonyms().canonical("K8s")).is_equal_to(Some(&"kubernetes".to_string()));
+    }
+
+    #[test]
+    fn normalises_tag_aliases_without_duplicating_tags() {
+        let tags = vec![
+            "k8s".to_string(),
+            "kubernetes".to_string(),
+            "ops".to_string(),
+        ];
+
+        assert_that(&synonyms().normalise_tags(&tags))
+            .is_equal_to(vec!["kubernetes".to_string(), "ops".to_string()]);
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::config::{AppConfig, AppConfigError};
+use crate::domain::bookmark::{BookmarkRepository, BookmarkRepositoryError};
+use crate::ports::persistence::file_system::{
+    import_bookmarks as import_into_file, writable_bookmark_file, BookmarkFileFormat,
+    BookmarkImportFormat, BookmarkImportReport, FileSystemBookmarkRepositoryAdapter,
+    FileSystemBookmarkRepositoryAdapterError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    NetscapeHtml,
+    Chromium,
+    Firefox,
+    Xbel,
+    Csv,
+    Pinboard,
+    Linkding,
+}
+
+impl FromStr for ImportFormat {
+    type Err = ImportError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "netscape" | "html" => Ok(ImportFormat::NetscapeHtml),
+            "chromium" | "chrome" => Ok(ImportFormat::Chromium),
+            "firefox" => Ok(ImportFormat::Firefox),
+            "xbel" => Ok(ImportFormat::Xbel),
+            "csv" => Ok(ImportFormat::Csv),
+            "pinboard" => Ok(ImportFormat::Pinboard),
+            "linkding" => Ok(ImportFormat::Linkding),
+            _ => Err(map_import_error_cause(ImportErrorCause::UnknownFormat(
+                format.to_string(),
+            ))),
+        }
+    }
+}
+
+impl ImportFormat {
+    fn bookmark_import_format(self, config: &AppConfig) -> BookmarkImportFormat {
+        match self {
+            ImportFormat::NetscapeHtml => BookmarkImportFormat::NetscapeHtml,
+            ImportFormat::Chromium => BookmarkImportFormat::Chromium,
+            ImportFormat::Firefox => BookmarkImportFormat::Firefox,
+            ImportFormat::Xbel => BookmarkImportFormat::Xbel,
+            ImportFormat::Csv => BookmarkImportFormat::Csv(config.csv().clone()),
+            ImportFormat::Pinboard => BookmarkImportFormat::Pinboard,
+            ImportFormat::Linkding => BookmarkImportFormat::Linkding,
+        }
+    }
+}
+
+pub struct ImportReport {
+    target_file: PathBuf,
+    inner: BookmarkImportReport,
+}
+
+impl ImportReport {
+    pub fn target_file(&self) -> &Path {
+        self.target_file.as_path()
+    }
+
+    pub fn added(&self) -> &Vec<String> {
+        self.inner.added()
+    }
+
+    pub fn skipped(&self) -> &Vec<String> {
+        self.inner.skipped()
+    }
+
+    pub fn invalid(&self) -> &Vec<String> {
+        self.inner.invalid()
+    }
+}
+
+pub fn import_bookmarks<P: AsRef<Path>, I: AsRef<Path>>(
+    config_path: P,
+    import_file: I,
+    format: ImportFormat,
+    target_file: Option<PathBuf>,
+) -> Result<ImportReport, ImportError> {
+    let config = AppConfig::load(config_path.as_ref()).map_err(map_import_error_cause)?;
+    let (target_file, target_format) = match target_file {
+        Some(target_file) => {
+            let target_format = BookmarkFileFormat::detect(target_file.as_path());
+            (target_file, target_format)
+        }
+        None => default_target_file(&config)?,
+    };
+    let mut bookmark_repository = FileSystemBookmarkRepositoryAdapter::new(
+        config.bookmark_sources().clone(),
+        config.synonyms().clone(),
+    );
+    bookmark_repository
+        .initialise()
+        .map_err(map_import_error_cause)?;
+    let existing_bookmarks = bookmark_repository
+        .get_all()
+        .map_err(map_import_error_cause)?;
+
+    let report = import_into_file(
+        import_file.as_ref(),
+        format.bookmark_import_format(&config),
+        &existing_bookmarks,
+        target_file.as_path(),
+        target_format,
+    )
+    .map_err(map_import_error_cause)?;
+
+    Ok(ImportReport {
+        target_file,
+        inner: report,
+    })
+}
+
+fn default_target_file(config: &AppConfig) -> Result<(PathBuf, BookmarkFileFormat), ImportError> {
+    writable_bookmark_file(config.bookmark_sources())
+        .map(|(source, format)| (source.path().to_path_buf(), format))
+        .ok_or_else(|| map_import_error_cause(ImportErrorCause::NoTargetFile))
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Could not import bookmarks: {cause}")]
+pub struct ImportError {
+    cause: ImportErrorCause,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum ImportErrorCause {
+    #[error("Unknown import format: {0}")]
+    UnknownFormat(String),
+    #[error("No writable bookmark file to import into, specify one explicitly")]
+    NoTargetFile,
+    #[error(transparent)]
+    Config(#[from] AppConfigError),
+    #[error(transparent)]
+    Repository(#[from] BookmarkRepositoryError),
+    #[error(transparent)]
+    FileSystemRepository(#[from] FileSystemBookmarkRepositoryAdapterError),
+}
+
+fn map_import_error_cause<C: Into<ImportErrorCause>>(cause: C) -> ImportError {
+    ImportError {
+        cause: cause.into(),
+    }
+}
@@ -1,6 +1,11 @@
 pub use app::*;
+pub use convert::*;
+pub use import::*;
 
 mod app;
 pub(crate) mod application;
+pub(crate) mod config;
+mod convert;
 pub(crate) mod domain;
+mod import;
 pub(crate) mod ports;
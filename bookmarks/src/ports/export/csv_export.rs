@@ -0,0 +1,61 @@
+use crate::domain::bookmark::Bookmark;
+use crate::ports::persistence::file_system::CsvColumns;
+
+pub(crate) fn bookmarks_csv(
+    bookmarks: &[Bookmark],
+    columns: &CsvColumns,
+) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(columns.headers().iter())?;
+
+    let mut bookmarks: Vec<&Bookmark> = bookmarks.iter().collect();
+    bookmarks.sort_by(|a, b| a.name().cmp(b.name()));
+    for bookmark in bookmarks {
+        writer.write_record(&[
+            bookmark.url().to_string(),
+            bookmark.name().clone(),
+            bookmark.description().cloned().unwrap_or_default(),
+            bookmark.tags().join(columns.tag_separator()),
+            bookmark.aliases().join(columns.tag_separator()),
+            bookmark
+                .created_at()
+                .map(|created_at| columns.format_date(&created_at))
+                .unwrap_or_default(),
+        ])?;
+    }
+
+    let content = writer
+        .into_inner()
+        .map_err(|err| csv::Error::from(err.into_error()))?;
+    Ok(String::from_utf8_lossy(&content).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use spectral::prelude::*;
+    use url::Url;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn writes_mapped_headers_and_joined_tags() {
+        let bookmarks = vec![Bookmark::new(
+            Uuid::new_v4(),
+            Url::parse("https://grafana.example.com").unwrap(),
+            "Grafana, prod".to_string(),
+            Some("Dashboards".to_string()),
+            vec!["metrics".to_string(), "ops".to_string()],
+        )
+        .with_created_at(Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap()))];
+
+        let csv = bookmarks_csv(&bookmarks, &CsvColumns::default()).unwrap();
+
+        assert_that(&csv).is_equal_to(
+            "url,name,description,tags,aliases,created_at\n\
+             https://grafana.example.com/,\"Grafana, prod\",Dashboards,\"metrics,ops\",,2020-09-13T12:26:40+00:00\n"
+                .to_string(),
+        );
+    }
+}
@@ -0,0 +1,5 @@
+pub(crate) use csv_export::*;
+pub(crate) use netscape_html_export::*;
+
+mod csv_export;
+mod netscape_html_export;
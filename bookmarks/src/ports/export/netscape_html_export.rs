@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use crate::domain::bookmark::Bookmark;
+
+const HEADER: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+"#;
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct ExportConfig {
+    primary_tags: Vec<String>,
+}
+
+impl ExportConfig {
+    pub(crate) fn new(primary_tags: Vec<String>) -> Self {
+        ExportConfig { primary_tags }
+    }
+
+    fn folder<'a>(&self, bookmark: &'a Bookmark) -> Option<&'a String> {
+        self.primary_tags
+            .iter()
+            .find_map(|primary_tag| bookmark.tags().iter().find(|tag| *tag == primary_tag))
+            .or_else(|| bookmark.tags().first())
+    }
+}
+
+pub(crate) fn netscape_html(bookmarks: &[Bookmark], export_config: &ExportConfig) -> String {
+    let mut folders: BTreeMap<&String, Vec<&Bookmark>> = BTreeMap::new();
+    let mut unfiled: Vec<&Bookmark> = vec![];
+    bookmarks
+        .iter()
+        .for_each(|bookmark| match export_config.folder(bookmark) {
+            Some(folder) => folders.entry(folder).or_default().push(bookmark),
+            None => unfiled.push(bookmark),
+        });
+
+    let mut html = String::from(HEADER);
+    html.push_str("<DL><p>\n");
+    folders.into_iter().for_each(|(folder, mut bookmarks)| {
+        html.push_str(&format!("    <DT><H3>{}</H3>\n", escape_html(folder)));
+        html.push_str("    <DL><p>\n");
+        bookmarks.sort_by(|a, b| a.name().cmp(b.name()));
+        bookmarks
+            .iter()
+            .for_each(|bookmark| write_bookmark(&mut html, bookmark, "        "));
+        html.push_str("    </DL><p>\n");
+    });
+    unfiled.sort_by(|a, b| a.name().cmp(b.name()));
+    unfiled
+        .iter()
+        .for_each(|bookmark| write_bookmark(&mut html, bookmark, "    "));
+    html.push_str("</DL><p>\n");
+
+    html
+}
+
+fn write_bookmark(html: &mut String, bookmark: &Bookmark, indent: &str) {
+    let mut attributes = format!("HREF=\"{}\"", escape_html(bookmark.url().as_str()));
+    if let Some(created_at) = bookmark.created_at() {
+        attributes.push_str(&format!(" ADD_DATE=\"{}\"", created_at.timestamp()));
+    }
+    if let Some(updated_at) = bookmark.updated_at() {
+        attributes.push_str(&format!(" LAST_MODIFIED=\"{}\"", updated_at.timestamp()));
+    }
+    if let Some(last_visited_at) = bookmark.last_visited_at() {
+        attributes.push_str(&format!(" LAST_VISIT=\"{}\"", last_visited_at.timestamp()));
+    }
+    if !bookmark.tags().is_empty() {
+        attributes.push_str(&format!(
+            " TAGS=\"{}\"",
+            escape_html(&bookmark.tags().join(","))
+        ));
+    }
+    if let Some(alias) = bookmark.aliases().first() {
+        attributes.push_str(&format!(" SHORTCUTURL=\"{}\"", escape_html(alias)));
+    }
+
+    html.push_str(&format!(
+        "{}<DT><A {}>{}</A>\n",
+        indent,
+        attributes,
+        escape_html(bookmark.name())
+    ));
+    if let Some(description) = bookmark.description() {
+        html.push_str(&format!("{}<DD>{}\n", indent, escape_html(description)));
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use spectral::prelude::*;
+    use url::Url;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn bookmark(name: &str, url: &str, tags: &[&str]) -> Bookmark {
+        Bookmark::new(
+            Uuid::new_v4(),
+            Url::parse(url).unwrap(),
+            name.to_string(),
+            None,
+            tags.iter().map(|tag| tag.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn files_bookmarks_under_their_primary_tag_with_aliases_as_keywords() {
+        let bookmarks = vec![
+            bookmark(
+                "Grafana",
+                "https://grafana.example.com",
+                &["metrics", "platform"],
+            )
+            .with_aliases(vec!["gf".to_string()])
+            .with_created_at(Some(Utc.timestamp(1_600_000_000, 0)))
+            .with_updated_at(Some(Utc.timestamp(1_700_000_000, 0))),
+            bookmark("Q&A", "https://qa.example.com", &["docs"]),
+            bookmark("GitHub", "https://github.com", &[]),
+        ];
+
+        let html = netscape_html(&bookmarks, &ExportConfig::new(vec!["platform".to_string()]));
+
+        assert_that(&html.trim_start_matches(HEADER)).is_equal_to(
+            r#"<DL><p>
+    <DT><H3>docs</H3>
+    <DL><p>
+        <DT><A HREF="https://qa.example.com/" TAGS="docs">Q&amp;A</A>
+    </DL><p>
+    <DT><H3>platform</H3>
+    <DL><p>
+        <DT><A HREF="https://grafana.example.com/" ADD_DATE="1600000000" LAST_MODIFIED="1700000000" TAGS="metrics,platform" SHORTCUTURL="gf">Grafana</A>
+    </DL><p>
+    <DT><A HREF="https://github.com/">GitHub</A>
+</DL><p>
+"#,
+        );
+    }
+}
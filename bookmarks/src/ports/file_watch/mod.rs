@@ -0,0 +1,3 @@
+pub(crate) use notify_file_watcher::*;
+
+mod notify_file_watcher;
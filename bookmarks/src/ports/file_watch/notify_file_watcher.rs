@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(500);
+
+pub(crate) struct NotifyFileWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl NotifyFileWatcher {
+    pub(crate) fn watch<F>(files: &[&Path], on_change: F) -> Result<Self, NotifyFileWatcherError>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let files: Vec<PathBuf> = files
+            .iter()
+            .map(|file| file.canonicalize())
+            .collect::<Result<Vec<PathBuf>, std::io::Error>>()?;
+        let (sender, receiver) = channel();
+        let mut watcher = watcher(sender, DEBOUNCE_DELAY)?;
+
+        let mut directories: Vec<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+        directories.sort();
+        directories.dedup();
+        for directory in directories {
+            watcher.watch(directory, RecursiveMode::NonRecursive)?;
+        }
+
+        std::thread::spawn(move || {
+            receiver
+                .iter()
+                .filter(|event| {
+                    changed_path(event).is_some_and(|path| files.iter().any(|file| file == path))
+                })
+                .for_each(|_| on_change());
+        });
+
+        Ok(NotifyFileWatcher { _watcher: watcher })
+    }
+}
+
+fn changed_path(event: &DebouncedEvent) -> Option<&PathBuf> {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Chmod(path)
+        | DebouncedEvent::Remove(path)
+        | DebouncedEvent::Rename(_, path) => Some(path),
+        _ => None,
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum NotifyFileWatcherError {
+    #[error("Could not resolve watched file: {0}")]
+    UnresolvedFile(#[from] std::io::Error),
+    #[error(transparent)]
+    Notify(#[from] notify::Error),
+}
@@ -1 +1 @@
-pub(crate) mod warp;
\ No newline at end of file
+pub(crate) mod warp;
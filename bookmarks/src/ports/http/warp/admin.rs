@@ -0,0 +1,229 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use warp::http::header::{AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE};
+use warp::http::{Response, StatusCode};
+use warp::{Filter, Reply};
+
+use crate::application::{ApplicationServiceError, ReloadService};
+use crate::domain::bookmark::Editor;
+use crate::ports::http::warp::disable_caching::disable_caching;
+
+/// A user allowed to call the admin endpoints, identified by their bearer token.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AdminUser {
+    name: String,
+    #[serde(default)]
+    email: Option<String>,
+    token: String,
+}
+
+impl AdminUser {
+    pub(crate) fn new(name: String, email: Option<String>, token: String) -> Self {
+        AdminUser { name, email, token }
+    }
+
+    fn editor(&self) -> Editor {
+        Editor::new(self.name.clone(), self.email.clone())
+    }
+}
+
+pub(crate) fn admin_reload_filter<RS>(
+    reload_service: Arc<RS>,
+    admin_users: Vec<AdminUser>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    RS: ReloadService + Send + Sync + 'static,
+{
+    warp::post()
+        .and(warp::header::optional::<String>(AUTHORIZATION.as_str()))
+        .and(warp::any().map(move || admin_users.clone()))
+        .and(warp::any().map(move || reload_service.clone()))
+        .and_then(reload_handler)
+        .map(disable_caching)
+}
+
+async fn reload_handler<RS>(
+    authorization: Option<String>,
+    admin_users: Vec<AdminUser>,
+    reload_service: Arc<RS>,
+) -> Result<warp::reply::Response, Infallible>
+where
+    RS: ReloadService + Send + Sync + 'static,
+{
+    if let Err(rejection) = authenticate(authorization.as_deref(), &admin_users) {
+        return Ok(rejection.into_response());
+    }
+
+    let result = tokio::task::spawn_blocking(move || reload_service.reload())
+        .await
+        .unwrap_or_else(|err| Err(ApplicationServiceError::Reload(err.to_string())));
+    let (status, body) = match result {
+        Ok(bookmark_count) => (StatusCode::OK, ReloadResponse::success(bookmark_count)),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ReloadResponse::failure(err.to_string()),
+        ),
+    };
+
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(&body).unwrap())
+        .into_response())
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum AdminRejection {
+    Disabled,
+    Unauthorised,
+}
+
+impl AdminRejection {
+    pub(super) fn into_response(self) -> warp::reply::Response {
+        match self {
+            AdminRejection::Disabled => Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(String::from("Admin endpoints are not enabled."))
+                .into_response(),
+            AdminRejection::Unauthorised => Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .header(WWW_AUTHENTICATE, "Bearer")
+                .body(String::from("Missing or invalid admin token."))
+                .into_response(),
+        }
+    }
+}
+
+/// Finds the admin user the request is authorised as.
+pub(super) fn authenticate(
+    authorization: Option<&str>,
+    admin_users: &[AdminUser],
+) -> Result<Editor, AdminRejection> {
+    if admin_users.is_empty() {
+        return Err(AdminRejection::Disabled);
+    }
+    admin_users
+        .iter()
+        .find(|admin_user| is_authorised(authorization, admin_user.token.as_str()))
+        .map(AdminUser::editor)
+        .ok_or(AdminRejection::Unauthorised)
+}
+
+fn is_authorised(authorization: Option<&str>, admin_token: &str) -> bool {
+    match authorization.and_then(|authorization| authorization.strip_prefix("Bearer ")) {
+        Some(token) => {
+            token.len() == admin_token.len()
+                && token
+                    .bytes()
+                    .zip(admin_token.bytes())
+                    .fold(0, |difference, (a, b)| difference | (a ^ b))
+                    == 0
+        }
+        None => false,
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ReloadResponse {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bookmark_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ReloadResponse {
+    fn success(bookmark_count: usize) -> Self {
+        ReloadResponse {
+            success: true,
+            bookmark_count: Some(bookmark_count),
+            error: None,
+        }
+    }
+
+    fn failure(error: String) -> Self {
+        ReloadResponse {
+            success: false,
+            bookmark_count: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn only_authorises_matching_bearer_token() {
+        assert_that(&is_authorised(Some("Bearer secret"), "secret")).is_true();
+        assert_that(&is_authorised(Some("Bearer secreT"), "secret")).is_false();
+        assert_that(&is_authorised(Some("secret"), "secret")).is_false();
+        assert_that(&is_authorised(None, "secret")).is_false();
+    }
+
+    #[test]
+    fn authenticates_as_the_user_owning_the_token() {
+        let admin_users = vec![
+            AdminUser::new("admin".to_string(), None, "secret".to_string()),
+            AdminUser::new(
+                "Ada".to_string(),
+                Some("ada@example.com".to_string()),
+                "ada-token".to_string(),
+            ),
+        ];
+
+        assert_that(&authenticate(Some("Bearer ada-token"), &admin_users)).is_equal_to(Ok(
+            Editor::new("Ada".to_string(), Some("ada@example.com".to_string())),
+        ));
+        assert_that(&authenticate(Some("Bearer other"), &admin_users))
+            .is_equal_to(Err(AdminRejection::Unauthorised));
+        assert_that(&authenticate(Some("Bearer secret"), &[]))
+            .is_equal_to(Err(AdminRejection::Disabled));
+    }
+
+    struct FixedReloadService;
+
+    impl ReloadService for FixedReloadService {
+        fn reload(&self) -> Result<usize, ApplicationServiceError> {
+            Ok(2)
+        }
+    }
+
+    async fn reload(admin_users: Vec<AdminUser>, authorization: &str) -> (StatusCode, String) {
+        let response = warp::test::request()
+            .method("POST")
+            .header(AUTHORIZATION, authorization)
+            .reply(&admin_reload_filter(
+                Arc::new(FixedReloadService),
+                admin_users,
+            ))
+            .await;
+        (
+            response.status(),
+            String::from_utf8(response.body().to_vec()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn reloads_only_when_enabled_and_authorised() {
+        let admin_users = vec![AdminUser::new(
+            "admin".to_string(),
+            None,
+            "secret".to_string(),
+        )];
+
+        let (disabled, _) = reload(vec![], "Bearer secret").await;
+        let (unauthorised, _) = reload(admin_users.clone(), "Bearer other").await;
+        let (authorised, body) = reload(admin_users, "Bearer secret").await;
+
+        assert_that(&disabled).is_equal_to(StatusCode::NOT_FOUND);
+        assert_that(&unauthorised).is_equal_to(StatusCode::UNAUTHORIZED);
+        assert_that(&authorised).is_equal_to(StatusCode::OK);
+        assert_that(&body).is_equal_to(r#"{"success":true,"bookmark_count":2}"#.to_string());
+    }
+}
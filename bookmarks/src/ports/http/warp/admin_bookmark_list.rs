@@ -0,0 +1,230 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use warp::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use warp::http::{Response, StatusCode};
+use warp::{Filter, Reply};
+
+use crate::application::ApplicationService;
+use crate::domain::bookmark::Bookmark;
+use crate::ports::http::warp::admin::{authenticate, AdminUser};
+use crate::ports::http::warp::admin_bookmarks::{
+    error_response, json_response, BookmarkEditResponse,
+};
+use crate::ports::http::warp::disable_caching::disable_caching;
+
+/// `GET /` lists the bookmarks with their timestamps and visits.
+pub(crate) fn admin_bookmark_list_filter<AS>(
+    application_service: Arc<AS>,
+    admin_users: Vec<AdminUser>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    AS: ApplicationService + Send + Sync,
+{
+    warp::path::end()
+        .and(warp::get())
+        .and(warp::header::optional::<String>(AUTHORIZATION.as_str()))
+        .and(warp::query::<ListQuery>())
+        .and(warp::any().map(move || admin_users.clone()))
+        .and(warp::any().map(move || application_service.clone()))
+        .map(list_handler)
+        .map(disable_caching)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ListQuery {
+    #[serde(default)]
+    sort: Option<String>,
+}
+
+fn list_handler<AS: ApplicationService>(
+    authorization: Option<String>,
+    query: ListQuery,
+    admin_users: Vec<AdminUser>,
+    application_service: Arc<AS>,
+) -> warp::reply::Response {
+    if let Err(rejection) = authenticate(authorization.as_deref(), &admin_users) {
+        return rejection.into_response();
+    }
+    let sort = match query.sort.as_deref().map(BookmarkSort::parse) {
+        Some(Some(sort)) => sort,
+        Some(None) => {
+            return json_response(
+                StatusCode::BAD_REQUEST,
+                &BookmarkEditResponse::failure(format!(
+                    "Cannot sort by {}, use one of {}",
+                    query.sort.unwrap_or_default(),
+                    BookmarkSort::FIELDS.join(", ")
+                )),
+            )
+        }
+        None => BookmarkSort::default(),
+    };
+
+    match application_service.bookmarks() {
+        Ok(mut bookmarks) => {
+            bookmarks.sort_by(|a, b| sort.compare(a, b));
+            let body = bookmarks
+                .iter()
+                .map(BookmarkDetails::from)
+                .collect::<Vec<BookmarkDetails>>();
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(CONTENT_TYPE, "application/json")
+                .body(serde_json::to_string(&body).unwrap())
+                .into_response()
+        }
+        Err(err) => error_response(err),
+    }
+}
+
+/// `sort=field` lists bookmarks in ascending order of a field, `sort=-field` in descending order.
+/// Bookmarks without a timestamp come first in ascending order, so `sort=last_visited_at` starts
+/// with the bookmarks nobody has opened.
+#[derive(Debug, Default)]
+struct BookmarkSort {
+    field: BookmarkSortField,
+    descending: bool,
+}
+
+#[derive(Debug, Default)]
+enum BookmarkSortField {
+    #[default]
+    Name,
+    CreatedAt,
+    UpdatedAt,
+    LastVisitedAt,
+    VisitCount,
+}
+
+impl BookmarkSort {
+    const FIELDS: [&'static str; 5] = [
+        "name",
+        "created_at",
+        "updated_at",
+        "last_visited_at",
+        "visit_count",
+    ];
+
+    fn parse(sort: &str) -> Option<Self> {
+        let (field, descending) = match sort.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (sort, false),
+        };
+        let field = match field {
+            "name" => BookmarkSortField::Name,
+            "created_at" => BookmarkSortField::CreatedAt,
+            "updated_at" => BookmarkSortField::UpdatedAt,
+            "last_visited_at" => BookmarkSortField::LastVisitedAt,
+            "visit_count" => BookmarkSortField::VisitCount,
+            _ => return None,
+        };
+        Some(BookmarkSort { field, descending })
+    }
+
+    fn compare(&self, a: &Bookmark, b: &Bookmark) -> Ordering {
+        let ordering = match self.field {
+            BookmarkSortField::Name => Ordering::Equal,
+            BookmarkSortField::CreatedAt => a.created_at().cmp(&b.created_at()),
+            BookmarkSortField::UpdatedAt => a.updated_at().cmp(&b.updated_at()),
+            BookmarkSortField::LastVisitedAt => a.last_visited_at().cmp(&b.last_visited_at()),
+            BookmarkSortField::VisitCount => a.visit_count().cmp(&b.visit_count()),
+        }
+        .then_with(|| a.name().cmp(b.name()));
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct BookmarkDetails {
+    id: String,
+    url: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    tags: Vec<String>,
+    aliases: Vec<String>,
+    group_path: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    last_visited_at: Option<DateTime<Utc>>,
+    visit_count: u64,
+}
+
+impl From<&Bookmark> for BookmarkDetails {
+    fn from(bookmark: &Bookmark) -> Self {
+        BookmarkDetails {
+            id: bookmark.id().to_string(),
+            url: bookmark.url().to_string(),
+            name: bookmark.name().clone(),
+            description: bookmark.description().cloned(),
+            tags: bookmark.tags().clone(),
+            aliases: bookmark.aliases().clone(),
+            group_path: bookmark.group_path().clone(),
+            created_at: bookmark.created_at(),
+            updated_at: bookmark.updated_at(),
+            last_visited_at: bookmark.last_visited_at(),
+            visit_count: bookmark.visit_count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use spectral::prelude::*;
+    use url::Url;
+
+    use crate::domain::bookmark::BookmarkVisits;
+
+    use super::*;
+
+    fn bookmark(name: &str, visits: u64, last_visited_at: Option<i64>) -> Bookmark {
+        let url = Url::parse(&format!("https://{}.example.com", name)).unwrap();
+        Bookmark::new(Bookmark::id_for(&url), url, name.to_string(), None, vec![]).with_visits(
+            &BookmarkVisits::new(
+                visits,
+                last_visited_at.map(|seconds| Utc.timestamp_opt(seconds, 0).unwrap()),
+            ),
+        )
+    }
+
+    fn sorted(sort: &str) -> Vec<String> {
+        let sort = BookmarkSort::parse(sort).unwrap();
+        let mut bookmarks = [
+            bookmark("grafana", 3, Some(1_700_000_000)),
+            bookmark("wiki", 1, Some(1_600_000_000)),
+            bookmark("jira", 0, None),
+        ];
+        bookmarks.sort_by(|a, b| sort.compare(a, b));
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.name().clone())
+            .collect()
+    }
+
+    #[test]
+    fn sorts_by_a_field_in_either_direction() {
+        assert_that(&sorted("name")).is_equal_to(vec![
+            "grafana".to_string(),
+            "jira".to_string(),
+            "wiki".to_string(),
+        ]);
+        assert_that(&sorted("-visit_count")).is_equal_to(vec![
+            "grafana".to_string(),
+            "wiki".to_string(),
+            "jira".to_string(),
+        ]);
+        assert_that(&sorted("last_visited_at")).is_equal_to(vec![
+            "jira".to_string(),
+            "wiki".to_string(),
+            "grafana".to_string(),
+        ]);
+        assert_that(&BookmarkSort::parse("-url").is_none()).is_true();
+    }
+}
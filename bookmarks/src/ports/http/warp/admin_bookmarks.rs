@@ -0,0 +1,186 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use url::Url;
+use uuid::Uuid;
+use warp::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use warp::http::{Response, StatusCode};
+use warp::{Filter, Reply};
+
+use crate::application::{ApplicationServiceError, BookmarkEditService};
+use crate::domain::bookmark::{Bookmark, BookmarkChange, BookmarkRepositoryError};
+use crate::ports::http::warp::admin::{authenticate, AdminUser};
+use crate::ports::http::warp::disable_caching::disable_caching;
+
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// `POST /` adds or updates a bookmark by url, `DELETE /{id}` removes one.
+pub(crate) fn admin_bookmarks_filter<ES>(
+    edit_service: Arc<ES>,
+    admin_users: Vec<AdminUser>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    ES: BookmarkEditService + Send + Sync + 'static,
+{
+    let save_edit_service = edit_service.clone();
+    let save_admin_users = admin_users.clone();
+    let save = warp::path::end()
+        .and(warp::post())
+        .and(warp::header::optional::<String>(AUTHORIZATION.as_str()))
+        .and(warp::body::content_length_limit(MAX_BODY_BYTES))
+        .and(warp::body::json())
+        .and(warp::any().map(move || save_admin_users.clone()))
+        .and(warp::any().map(move || save_edit_service.clone()))
+        .and_then(save_handler);
+    let remove = warp::path::param::<Uuid>()
+        .and(warp::path::end())
+        .and(warp::delete())
+        .and(warp::header::optional::<String>(AUTHORIZATION.as_str()))
+        .and(warp::any().map(move || admin_users.clone()))
+        .and(warp::any().map(move || edit_service.clone()))
+        .and_then(remove_handler);
+
+    save.or(remove).unify().map(disable_caching)
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BookmarkRequest {
+    url: Url,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+impl BookmarkRequest {
+    fn into_bookmark(self) -> Bookmark {
+        Bookmark::new(
+            Bookmark::id_for(&self.url),
+            self.url,
+            self.name.trim().to_string(),
+            self.description
+                .filter(|description| !description.trim().is_empty()),
+            self.tags,
+        )
+        .with_aliases(self.aliases)
+    }
+}
+
+async fn save_handler<ES>(
+    authorization: Option<String>,
+    request: BookmarkRequest,
+    admin_users: Vec<AdminUser>,
+    edit_service: Arc<ES>,
+) -> Result<warp::reply::Response, Infallible>
+where
+    ES: BookmarkEditService + Send + Sync + 'static,
+{
+    let editor = match authenticate(authorization.as_deref(), &admin_users) {
+        Ok(editor) => editor,
+        Err(rejection) => return Ok(rejection.into_response()),
+    };
+    if request.name.trim().is_empty() {
+        return Ok(json_response(
+            StatusCode::BAD_REQUEST,
+            &BookmarkEditResponse::failure("Bookmark name must not be empty".to_string()),
+        ));
+    }
+
+    let bookmark = request.into_bookmark();
+    let id = bookmark.id();
+    let result = tokio::task::spawn_blocking(move || edit_service.save_bookmark(bookmark, editor))
+        .await
+        .unwrap_or_else(|err| Err(ApplicationServiceError::Reload(err.to_string())));
+    Ok(match result {
+        Ok(change) => json_response(
+            match change {
+                BookmarkChange::Added => StatusCode::CREATED,
+                BookmarkChange::Updated => StatusCode::OK,
+            },
+            &BookmarkEditResponse::success(id, Some(change)),
+        ),
+        Err(err) => error_response(err),
+    })
+}
+
+async fn remove_handler<ES>(
+    id: Uuid,
+    authorization: Option<String>,
+    admin_users: Vec<AdminUser>,
+    edit_service: Arc<ES>,
+) -> Result<warp::reply::Response, Infallible>
+where
+    ES: BookmarkEditService + Send + Sync + 'static,
+{
+    let editor = match authenticate(authorization.as_deref(), &admin_users) {
+        Ok(editor) => editor,
+        Err(rejection) => return Ok(rejection.into_response()),
+    };
+
+    let result = tokio::task::spawn_blocking(move || edit_service.remove_bookmark(id, editor))
+        .await
+        .unwrap_or_else(|err| Err(ApplicationServiceError::Reload(err.to_string())));
+    Ok(match result {
+        Ok(_) => json_response(StatusCode::OK, &BookmarkEditResponse::success(id, None)),
+        Err(err) => error_response(err),
+    })
+}
+
+pub(super) fn error_response(err: ApplicationServiceError) -> warp::reply::Response {
+    let status = match &err {
+        ApplicationServiceError::Repository(BookmarkRepositoryError::BookmarkNotFound(_)) => {
+            StatusCode::NOT_FOUND
+        }
+        ApplicationServiceError::Repository(BookmarkRepositoryError::ReadOnly(_)) => {
+            StatusCode::CONFLICT
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    json_response(status, &BookmarkEditResponse::failure(err.to_string()))
+}
+
+pub(super) fn json_response(
+    status: StatusCode,
+    body: &BookmarkEditResponse,
+) -> warp::reply::Response {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(body).unwrap())
+        .into_response()
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(super) struct BookmarkEditResponse {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    change: Option<BookmarkChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl BookmarkEditResponse {
+    fn success(id: Uuid, change: Option<BookmarkChange>) -> Self {
+        BookmarkEditResponse {
+            success: true,
+            id: Some(id.to_string()),
+            change,
+            error: None,
+        }
+    }
+
+    pub(super) fn failure(error: String) -> Self {
+        BookmarkEditResponse {
+            success: false,
+            id: None,
+            change: None,
+            error: Some(error),
+        }
+    }
+}
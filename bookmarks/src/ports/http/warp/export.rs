@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use warp::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use warp::http::{Response, StatusCode};
+use warp::{Filter, Reply};
+
+use crate::application::ApplicationService;
+use crate::ports::export::{netscape_html, ExportConfig};
+use crate::ports::http::warp::disable_caching::disable_caching;
+use crate::ports::http::warp::search_error_handling::handle_search_error;
+use crate::ports::http::warp::with_application_service;
+
+pub(crate) fn bookmarks_export_filter<AS>(
+    application_service: Arc<AS>,
+    export_config: ExportConfig,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    AS: ApplicationService + Send + Sync,
+{
+    warp::get()
+        .and(with_application_service(application_service))
+        .and(warp::any().map(move || export_config.clone()))
+        .map(handler)
+        .map(disable_caching)
+}
+
+fn handler<AS: ApplicationService>(
+    application_service: Arc<AS>,
+    export_config: ExportConfig,
+) -> warp::reply::Response {
+    match application_service.bookmarks() {
+        Ok(bookmarks) => Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "text/html; charset=UTF-8")
+            .header(
+                CONTENT_DISPOSITION,
+                "attachment; filename=\"bookmarks.html\"",
+            )
+            .body(netscape_html(&bookmarks, &export_config))
+            .into_response(),
+        Err(err) => handle_search_error(&err),
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use url::form_urlencoded;
+use uuid::Uuid;
+use warp::http::{Response, StatusCode, Uri};
+use warp::{Filter, Reply};
+
+use crate::application::{ApplicationService, ApplicationServiceError};
+use crate::domain::bookmark::BookmarkRepositoryError;
+use crate::ports::http::warp::disable_caching::disable_caching;
+use crate::ports::http::warp::search_error_handling::handle_search_error;
+use crate::ports::http::warp::with_application_service;
+
+/// `GET /{id}?q=` records a visit to a bookmark and redirects to it. The redirect is temporary and
+/// not cached, so that every click is seen.
+pub(crate) fn bookmark_visit_filter<AS>(
+    application_service: Arc<AS>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    AS: ApplicationService + Send + Sync,
+{
+    warp::path::param::<Uuid>()
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_application_service(application_service))
+        .map(handler)
+        .map(disable_caching)
+}
+
+/// The link through which a bookmark found by `query` is opened.
+pub(super) fn visit_link(id: Uuid, query: &str) -> String {
+    format!(
+        "/go/{}?q={}",
+        id,
+        form_urlencoded::byte_serialize(query.as_bytes()).collect::<String>()
+    )
+}
+
+fn handler<AS: ApplicationService>(
+    id: Uuid,
+    mut p: HashMap<String, String>,
+    application_service: Arc<AS>,
+) -> warp::reply::Response {
+    match application_service.visit(id, p.remove("q")) {
+        Ok(url) => match Uri::from_str(url.as_str()) {
+            Ok(uri) => warp::redirect::temporary(uri).into_response(),
+            Err(_) => Response::builder()
+                .status(StatusCode::UNPROCESSABLE_ENTITY)
+                .body(format!(
+                    "Bookmark {} links to {}, which cannot be opened through a redirect.",
+                    id, url
+                ))
+                .into_response(),
+        },
+        Err(ApplicationServiceError::Repository(BookmarkRepositoryError::BookmarkNotFound(_))) => {
+            Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(format!("No bookmark with id {}.", id))
+                .into_response()
+        }
+        Err(err) => handle_search_error(&err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+    use url::Url;
+    use warp::http::header::LOCATION;
+
+    use crate::domain::bookmark::Bookmark;
+
+    use super::*;
+
+    struct FixedApplicationService {
+        url: Url,
+    }
+
+    impl ApplicationService for FixedApplicationService {
+        fn search(&self, _term: String) -> Result<Vec<Bookmark>, ApplicationServiceError> {
+            Ok(vec![])
+        }
+
+        fn suggest(&self, _term: String) -> Result<Vec<String>, ApplicationServiceError> {
+            Ok(vec![])
+        }
+
+        fn bookmarks(&self) -> Result<Vec<Bookmark>, ApplicationServiceError> {
+            Ok(vec![])
+        }
+
+        fn visit(&self, _id: Uuid, _query: Option<String>) -> Result<Url, ApplicationServiceError> {
+            Ok(self.url.clone())
+        }
+    }
+
+    async fn visit(url: &str) -> (StatusCode, Option<String>) {
+        let url = Url::parse(url).unwrap();
+        let response = warp::test::request()
+            .path(&format!("/{}?q=docs", Bookmark::id_for(&url)))
+            .reply(&bookmark_visit_filter(Arc::new(FixedApplicationService {
+                url,
+            })))
+            .await;
+        (
+            response.status(),
+            response
+                .headers()
+                .get(LOCATION)
+                .map(|location| location.to_str().unwrap().to_string()),
+        )
+    }
+
+    #[tokio::test]
+    async fn redirects_to_web_bookmarks_and_rejects_others() {
+        assert_that(&visit("https://doc.rust-lang.org/").await).is_equal_to((
+            StatusCode::TEMPORARY_REDIRECT,
+            Some("https://doc.rust-lang.org/".to_string()),
+        ));
+        assert_that(&visit("file:///home/ada/notes.html").await)
+            .is_equal_to((StatusCode::UNPROCESSABLE_ENTITY, None));
+    }
+
+    #[test]
+    fn links_to_the_bookmark_with_the_encoded_query() {
+        let id = Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://grafana.example.com/");
+
+        assert_that(&visit_link(id, "dash & logs"))
+            .is_equal_to(format!("/go/{}?q=dash+%26+logs", id));
+    }
+}
@@ -1,9 +1,20 @@
+pub(crate) use admin::*;
+pub(crate) use admin_bookmark_list::*;
+pub(crate) use admin_bookmarks::*;
 pub(crate) use application_service::*;
+pub(crate) use export::*;
+pub(crate) use go::*;
 pub(crate) use search::*;
 pub(crate) use suggestions::*;
 
+mod admin;
+mod admin_bookmark_list;
+mod admin_bookmarks;
 mod application_service;
 mod disable_caching;
+mod export;
+mod go;
+mod results_page;
 mod search;
 pub(super) mod search_error_handling;
 mod suggestions;
@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::bookmark::Bookmark;
+use crate::ports::export::escape_html;
+use crate::ports::http::warp::go::visit_link;
+
+/// Lists the bookmarks matching an ambiguous search, with when they were added, changed and last
+/// opened.
+pub(super) fn results_page(term: &str, bookmarks: &[Bookmark]) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n<title>{} - Bookmarks</title>\n</head>\n<body>\n",
+        escape_html(term)
+    );
+    if bookmarks.is_empty() {
+        html.push_str(&format!(
+            "<p>No bookmarks found for \"{}\".</p>\n",
+            escape_html(term)
+        ));
+    } else {
+        html.push_str("<ul>\n");
+        bookmarks
+            .iter()
+            .for_each(|bookmark| write_result(&mut html, term, bookmark));
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_result(html: &mut String, term: &str, bookmark: &Bookmark) {
+    html.push_str(&format!(
+        "<li>\n<a href=\"{}\">{}</a> <small>{}</small>\n",
+        escape_html(&visit_link(bookmark.id(), term)),
+        escape_html(bookmark.name()),
+        escape_html(bookmark.url().as_str())
+    ));
+    if let Some(description) = bookmark.description() {
+        html.push_str(&format!("<p>{}</p>\n", escape_html(description)));
+    }
+    if !bookmark.tags().is_empty() {
+        html.push_str(&format!(
+            "<p>Tags: {}</p>\n",
+            escape_html(&bookmark.tags().join(", "))
+        ));
+    }
+
+    let mut details = vec![];
+    if let Some(created_at) = bookmark.created_at() {
+        details.push(format!("Added {}", date(created_at)));
+    }
+    if let Some(updated_at) = bookmark.updated_at() {
+        details.push(format!("Updated {}", date(updated_at)));
+    }
+    details.push(match (bookmark.visit_count(), bookmark.last_visited_at()) {
+        (0, _) | (_, None) => "Never visited".to_string(),
+        (1, Some(last_visited_at)) => format!("Visited once, on {}", date(last_visited_at)),
+        (visit_count, Some(last_visited_at)) => format!(
+            "Visited {} times, last on {}",
+            visit_count,
+            date(last_visited_at)
+        ),
+    });
+    html.push_str(&format!(
+        "<p><small>{}</small></p>\n</li>\n",
+        details.join(" &middot; ")
+    ));
+}
+
+fn date(timestamp: DateTime<Utc>) -> String {
+    timestamp.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use spectral::prelude::*;
+    use url::Url;
+
+    use crate::domain::bookmark::BookmarkVisits;
+
+    use super::*;
+
+    fn bookmark(url: &str, name: &str) -> Bookmark {
+        let url = Url::parse(url).unwrap();
+        Bookmark::new(Bookmark::id_for(&url), url, name.to_string(), None, vec![])
+    }
+
+    #[test]
+    fn lists_links_with_their_timestamps_and_visits() {
+        let grafana = bookmark("https://grafana.example.com", "Grafana & Loki")
+            .with_created_at(Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap()))
+            .with_visits(&BookmarkVisits::new(
+                3,
+                Some(Utc.timestamp_opt(1_700_000_000, 0).unwrap()),
+            ));
+        let grafana_id = grafana.id();
+        let wiki = bookmark("https://wiki.example.com", "Wiki");
+
+        let html = results_page("<dash>", &[grafana, wiki]);
+
+        assert_that(&html).contains("<title>&lt;dash&gt; - Bookmarks</title>");
+        assert_that(&html).contains(&*format!(
+            "<a href=\"/go/{}?q=%3Cdash%3E\">Grafana &amp; Loki</a> <small>https://grafana.example.com/</small>",
+            grafana_id
+        ));
+        assert_that(&html).contains(
+            "<small>Added 2020-09-13 &middot; Visited 3 times, last on 2023-11-14</small>",
+        );
+        assert_that(&html).contains("<small>Never visited</small>");
+    }
+}
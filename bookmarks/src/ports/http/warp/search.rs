@@ -2,11 +2,14 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use warp::http::header::CONTENT_TYPE;
 use warp::http::{Response, StatusCode, Uri};
 use warp::{Filter, Reply};
 
 use crate::application::ApplicationService;
 use crate::ports::http::warp::disable_caching::disable_caching;
+use crate::ports::http::warp::go::visit_link;
+use crate::ports::http::warp::results_page::results_page;
 use crate::ports::http::warp::search_error_handling::handle_search_error;
 use crate::ports::http::warp::with_application_service;
 
@@ -29,19 +32,15 @@ fn handler<AS: ApplicationService>(
 ) -> warp::reply::Response {
     match p.get("q") {
         Some(term) => match application_service.search(term.clone()) {
-            Ok(urls) => {
-                if urls.len() == 1 {
-                    let url = urls.get(0).unwrap().clone();
-                    return warp::redirect(Uri::from_str(url.as_str()).unwrap()).into_response();
+            Ok(bookmarks) => {
+                if bookmarks.len() == 1 {
+                    let link = visit_link(bookmarks.first().unwrap().id(), term);
+                    return warp::redirect(Uri::from_str(&link).unwrap()).into_response();
                 }
 
                 Response::builder()
-                    .body(
-                        urls.iter()
-                            .map(|url| url.as_str().to_string())
-                            .collect::<Vec<String>>()
-                            .join(", "),
-                    )
+                    .header(CONTENT_TYPE, "text/html; charset=UTF-8")
+                    .body(results_page(term, &bookmarks))
                     .into_response()
             }
             Err(err) => handle_search_error(&err),
@@ -25,5 +25,12 @@ pub(crate) fn handle_search_error(err: &ApplicationServiceError) -> warp::reply:
                 .body(format!("{}", err))
                 .into_response(),
         },
+        ApplicationServiceError::Repository(_)
+        | ApplicationServiceError::Visits(_)
+        | ApplicationServiceError::Reload(_) => Response::builder()
+            .header(CACHE_CONTROL, "no-store")
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(format!("{}", err))
+            .into_response(),
     }
 }
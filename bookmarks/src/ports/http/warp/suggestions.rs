@@ -77,9 +77,10 @@ struct SuggestionResponseBody {
 
 impl From<SuggestionResponse> for SuggestionResponseBody {
     fn from(response: SuggestionResponse) -> Self {
-        let mut inner = vec![];
-        inner.push(StringOrStrings::String(response.query));
-        inner.push(StringOrStrings::Strings(response.suggestions));
+        let inner = vec![
+            StringOrStrings::String(response.query),
+            StringOrStrings::Strings(response.suggestions),
+        ];
 
         SuggestionResponseBody { inner }
     }
@@ -98,6 +99,6 @@ mod tests {
 
         let serialized = serde_json::to_string(&suggestion_response).unwrap();
 
-        assert_that(&serialized).is_equal_to(&"[\"query\",[\"suggestion1\"]]".to_string());
+        assert_that(&serialized).is_equal_to("[\"query\",[\"suggestion1\"]]".to_string());
     }
 }
@@ -1,3 +1,5 @@
+pub(crate) mod export;
+pub(crate) mod file_watch;
 pub(crate) mod http;
 pub(crate) mod persistence;
 pub(crate) mod search;
@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::domain::bookmark::{
+    Bookmark, BookmarkChange, BookmarkRepository, BookmarkRepositoryError, Editor,
+};
+use crate::domain::synonyms::Synonyms;
+use crate::ports::persistence::file_system::{BookmarkSource, FileSystemBookmarkRepositoryAdapter};
+use crate::ports::persistence::git::GitBookmarkRepositoryAdapter;
+use crate::ports::persistence::sqlite::SqliteBookmarkRepositoryAdapter;
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub(crate) enum RepositoryConfig {
+    #[default]
+    FileSystem,
+    Sqlite {
+        path: PathBuf,
+    },
+    Git {
+        path: PathBuf,
+        #[serde(default = "default_git_file")]
+        file: PathBuf,
+        #[serde(default)]
+        remote: Option<String>,
+        #[serde(default)]
+        branch: Option<String>,
+    },
+}
+
+fn default_git_file() -> PathBuf {
+    PathBuf::from("bookmarks.yml")
+}
+
+impl RepositoryConfig {
+    pub(crate) fn resolve_paths(self, config_directory: &Path) -> Self {
+        match self {
+            RepositoryConfig::Sqlite { path } => RepositoryConfig::Sqlite {
+                path: config_directory.join(path),
+            },
+            RepositoryConfig::Git {
+                path,
+                file,
+                remote,
+                branch,
+            } => RepositoryConfig::Git {
+                path: config_directory.join(path),
+                file,
+                remote,
+                branch,
+            },
+            RepositoryConfig::FileSystem => RepositoryConfig::FileSystem,
+        }
+    }
+}
+
+pub(crate) enum ConfiguredBookmarkRepository {
+    FileSystem(FileSystemBookmarkRepositoryAdapter),
+    Sqlite(SqliteBookmarkRepositoryAdapter),
+    Git(GitBookmarkRepositoryAdapter),
+}
+
+impl ConfiguredBookmarkRepository {
+    /// Opens the configured repository. An empty SQLite database or git repository without a
+    /// bookmark file is seeded from the bookmark sources.
+    pub(crate) fn load(
+        config: &RepositoryConfig,
+        sources: Vec<BookmarkSource>,
+        synonyms: Synonyms,
+    ) -> Result<Self, BookmarkRepositoryError> {
+        let mut file_system_repository =
+            FileSystemBookmarkRepositoryAdapter::new(sources, synonyms.clone());
+        let database_path = match config {
+            RepositoryConfig::FileSystem => {
+                file_system_repository.initialise()?;
+                return Ok(ConfiguredBookmarkRepository::FileSystem(
+                    file_system_repository,
+                ));
+            }
+            RepositoryConfig::Git {
+                path,
+                file,
+                remote,
+                branch,
+            } => {
+                let mut git_repository = GitBookmarkRepositoryAdapter::open(
+                    path,
+                    file,
+                    remote.as_deref(),
+                    branch.as_deref(),
+                    synonyms,
+                )?;
+                if !git_repository.has_bookmark_file() {
+                    file_system_repository.initialise()?;
+                    let imported = git_repository.import(&file_system_repository.get_all()?)?;
+                    log::info!("Imported {} bookmarks into {}", imported, path.display());
+                }
+                git_repository.initialise()?;
+                return Ok(ConfiguredBookmarkRepository::Git(git_repository));
+            }
+            RepositoryConfig::Sqlite { path } => path,
+        };
+
+        let sqlite_repository = SqliteBookmarkRepositoryAdapter::open(database_path)?;
+        if sqlite_repository.is_empty()? && file_system_repository.has_sources() {
+            file_system_repository.initialise()?;
+            let imported = sqlite_repository.import(&file_system_repository.get_all()?)?;
+            log::info!(
+                "Imported {} bookmarks into {}",
+                imported,
+                database_path.display()
+            );
+        }
+        Ok(ConfiguredBookmarkRepository::Sqlite(sqlite_repository))
+    }
+
+    /// Brings a git repository up to date with its remote. Other repositories have nothing to
+    /// pull.
+    pub(crate) fn pull(config: &RepositoryConfig) -> Result<(), BookmarkRepositoryError> {
+        if let RepositoryConfig::Git {
+            path,
+            remote: Some(remote),
+            branch,
+            ..
+        } = config
+        {
+            GitBookmarkRepositoryAdapter::pull(path, remote, branch.as_deref())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn files(&self) -> Vec<PathBuf> {
+        match self {
+            ConfiguredBookmarkRepository::FileSystem(repository) => repository.files(),
+            ConfiguredBookmarkRepository::Sqlite(_) => vec![],
+            ConfiguredBookmarkRepository::Git(repository) => repository.files(),
+        }
+    }
+}
+
+impl BookmarkRepository for ConfiguredBookmarkRepository {
+    fn get_all(&self) -> Result<Vec<Bookmark>, BookmarkRepositoryError> {
+        match self {
+            ConfiguredBookmarkRepository::FileSystem(repository) => repository.get_all(),
+            ConfiguredBookmarkRepository::Sqlite(repository) => repository.get_all(),
+            ConfiguredBookmarkRepository::Git(repository) => repository.get_all(),
+        }
+    }
+
+    fn get(&self, id: Uuid) -> Result<Bookmark, BookmarkRepositoryError> {
+        match self {
+            ConfiguredBookmarkRepository::FileSystem(repository) => repository.get(id),
+            ConfiguredBookmarkRepository::Sqlite(repository) => repository.get(id),
+            ConfiguredBookmarkRepository::Git(repository) => repository.get(id),
+        }
+    }
+
+    fn save(
+        &self,
+        bookmark: &Bookmark,
+        editor: &Editor,
+    ) -> Result<BookmarkChange, BookmarkRepositoryError> {
+        match self {
+            ConfiguredBookmarkRepository::FileSystem(repository) => {
+                repository.save(bookmark, editor)
+            }
+            ConfiguredBookmarkRepository::Sqlite(repository) => repository.save(bookmark, editor),
+            ConfiguredBookmarkRepository::Git(repository) => repository.save(bookmark, editor),
+        }
+    }
+
+    fn remove(&self, id: Uuid, editor: &Editor) -> Result<Bookmark, BookmarkRepositoryError> {
+        match self {
+            ConfiguredBookmarkRepository::FileSystem(repository) => repository.remove(id, editor),
+            ConfiguredBookmarkRepository::Sqlite(repository) => repository.remove(id, editor),
+            ConfiguredBookmarkRepository::Git(repository) => repository.remove(id, editor),
+        }
+    }
+}
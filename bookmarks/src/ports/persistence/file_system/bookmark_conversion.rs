@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use crate::ports::persistence::file_system::bookmark_file_format::BookmarkFileFormat;
+use crate::ports::persistence::file_system::bookmark_files::{parse_bookmark_file, BookmarkFile};
+use crate::ports::persistence::file_system::xbel::write_xbel;
+use crate::ports::persistence::file_system::FileSystemBookmarkRepositoryAdapterError;
+
+pub(crate) fn convert_to_yaml(
+    input: &Path,
+) -> Result<String, FileSystemBookmarkRepositoryAdapterError> {
+    read_document(input)?
+        .to_yaml()
+        .map_err(|err| FileSystemBookmarkRepositoryAdapterError::SerializeError(err.to_string()))
+}
+
+pub(crate) fn convert_to_xbel(
+    input: &Path,
+) -> Result<String, FileSystemBookmarkRepositoryAdapterError> {
+    Ok(write_xbel(&read_document(input)?))
+}
+
+fn read_document(input: &Path) -> Result<BookmarkFile, FileSystemBookmarkRepositoryAdapterError> {
+    let content = std::fs::read(input)
+        .map_err(|_| FileSystemBookmarkRepositoryAdapterError::ReadError(input.to_path_buf()))?;
+    parse_bookmark_file(input, BookmarkFileFormat::detect(input), content.as_slice()).map_err(
+        |err| {
+            FileSystemBookmarkRepositoryAdapterError::DeserializeError(format!(
+                "{}: {}",
+                input.display(),
+                err
+            ))
+        },
+    )
+}
@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::bookmark::{Bookmark, BookmarkChange};
+use crate::ports::persistence::file_system::bookmark_file_format::BookmarkFileFormat;
+use crate::ports::persistence::file_system::bookmark_files::{
+    read_bookmark_file, write_bookmark_file, BookmarkFile,
+};
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+use crate::ports::persistence::file_system::persistence_entry::PersistenceEntry;
+use crate::ports::persistence::file_system::FileSystemBookmarkRepositoryAdapterError;
+
+/// Replaces the bookmark in place or appends it, leaving out tags it already inherits from its
+/// source, file or groups.
+pub(super) fn save_bookmark_to_file(
+    file: &Path,
+    format: BookmarkFileFormat,
+    bookmark: &Bookmark,
+    inherited_tags: &[String],
+) -> Result<BookmarkChange, FileSystemBookmarkRepositoryAdapterError> {
+    let mut document = read_bookmark_file(file, format)?;
+    let mut tags = inherited_tags.to_vec();
+    tags.extend(document.tags().iter().cloned());
+
+    let change = if edit_entries(
+        document.entries_mut(),
+        bookmark.id(),
+        &tags,
+        &Edit::Save(bookmark),
+    ) {
+        BookmarkChange::Updated
+    } else {
+        document.push(PersistenceEntry::Bookmark(persistence_bookmark(
+            bookmark, &tags, None,
+        )));
+        BookmarkChange::Added
+    };
+    write_bookmark_file(file, format, &document)?;
+    Ok(change)
+}
+
+pub(super) fn remove_bookmark_from_file(
+    file: &Path,
+    format: BookmarkFileFormat,
+    id: Uuid,
+) -> Result<bool, FileSystemBookmarkRepositoryAdapterError> {
+    let mut document = read_bookmark_file(file, format)?;
+    let removed = edit_entries(document.entries_mut(), id, &[], &Edit::Remove);
+    if removed {
+        write_bookmark_file(file, format, &document)?;
+    }
+    Ok(removed)
+}
+
+/// Writes the bookmarks to a new flat bookmark file, ordered by name.
+pub(crate) fn write_bookmarks_to_file(
+    file: &Path,
+    format: BookmarkFileFormat,
+    bookmarks: &[Bookmark],
+) -> Result<(), FileSystemBookmarkRepositoryAdapterError> {
+    let mut bookmarks = bookmarks.iter().collect::<Vec<&Bookmark>>();
+    bookmarks.sort_by(|a, b| a.name().cmp(b.name()).then_with(|| a.url().cmp(b.url())));
+    let document = BookmarkFile::new(
+        vec![],
+        vec![],
+        bookmarks
+            .into_iter()
+            .map(|bookmark| PersistenceEntry::Bookmark(persistence_bookmark(bookmark, &[], None)))
+            .collect(),
+    );
+    write_bookmark_file(file, format, &document)
+}
+
+enum Edit<'a> {
+    Save(&'a Bookmark),
+    Remove,
+}
+
+fn edit_entries(
+    entries: &mut Vec<PersistenceEntry>,
+    id: Uuid,
+    inherited_tags: &[String],
+    edit: &Edit,
+) -> bool {
+    for index in 0..entries.len() {
+        let created_at = match &mut entries[index] {
+            PersistenceEntry::Bookmark(existing) if Bookmark::id_for(existing.url()) == id => {
+                existing.created_at()
+            }
+            PersistenceEntry::Bookmark(_) => continue,
+            PersistenceEntry::Group(group) => {
+                let mut group_tags = inherited_tags.to_vec();
+                group_tags.extend(group.tags().iter().cloned());
+                if edit_entries(group.entries_mut(), id, &group_tags, edit) {
+                    return true;
+                }
+                continue;
+            }
+        };
+        match edit {
+            Edit::Save(bookmark) => {
+                entries[index] = PersistenceEntry::Bookmark(persistence_bookmark(
+                    bookmark,
+                    inherited_tags,
+                    created_at,
+                ))
+            }
+            Edit::Remove => {
+                entries.remove(index);
+            }
+        }
+        return true;
+    }
+    false
+}
+
+fn persistence_bookmark(
+    bookmark: &Bookmark,
+    inherited_tags: &[String],
+    created_at: Option<DateTime<Utc>>,
+) -> PersistenceBookmark {
+    PersistenceBookmark::new(
+        bookmark.url().clone(),
+        bookmark.name().clone(),
+        bookmark.description().cloned(),
+        bookmark
+            .tags()
+            .iter()
+            .filter(|tag| !inherited_tags.contains(tag))
+            .cloned()
+            .collect(),
+        bookmark.aliases().clone(),
+        bookmark.created_at().or(created_at),
+    )
+    .with_updated_at(bookmark.updated_at())
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+    use url::Url;
+
+    use super::*;
+
+    fn grafana(name: &str) -> Bookmark {
+        let url = Url::parse("https://grafana.example.com").unwrap();
+        Bookmark::new(
+            Bookmark::id_for(&url),
+            url,
+            name.to_string(),
+            None,
+            vec!["platform".to_string(), "metrics".to_string()],
+        )
+    }
+
+    #[test]
+    fn updates_a_grouped_bookmark_in_place_and_appends_new_ones() {
+        let directory = tempfile::tempdir().unwrap();
+        let file = directory.path().join("bookmarks.yml");
+        std::fs::write(
+            &file,
+            "- group: Platform\n  tags: [platform]\n  bookmarks:\n    - name: Grafana\n      url: https://grafana.example.com/\n      created_at: 2020-09-13T12:26:40Z\n",
+        )
+        .unwrap();
+        let wiki_url = Url::parse("https://wiki.example.com").unwrap();
+        let wiki = Bookmark::new(
+            Bookmark::id_for(&wiki_url),
+            wiki_url,
+            "Wiki".to_string(),
+            None,
+            vec![],
+        );
+
+        let updated = save_bookmark_to_file(
+            &file,
+            BookmarkFileFormat::Yaml,
+            &grafana("Grafana prod"),
+            &[],
+        )
+        .unwrap();
+        let added = save_bookmark_to_file(&file, BookmarkFileFormat::Yaml, &wiki, &[]).unwrap();
+
+        assert_that(&updated).is_equal_to(BookmarkChange::Updated);
+        assert_that(&added).is_equal_to(BookmarkChange::Added);
+        assert_that(&std::fs::read_to_string(&file).unwrap()).is_equal_to(
+            "---\n- group: Platform\n  tags:\n    - platform\n  bookmarks:\n    - url: \"https://grafana.example.com/\"\n      name: Grafana prod\n      tags:\n        - metrics\n      created_at: \"2020-09-13T12:26:40Z\"\n- url: \"https://wiki.example.com/\"\n  name: Wiki\n"
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn removes_a_bookmark_and_leaves_the_file_untouched_when_missing() {
+        let directory = tempfile::tempdir().unwrap();
+        let file = directory.path().join("bookmarks.yml");
+        std::fs::write(
+            &file,
+            "- name: Grafana\n  url: https://grafana.example.com\n",
+        )
+        .unwrap();
+        let id = grafana("Grafana").id();
+
+        assert_that(&remove_bookmark_from_file(&file, BookmarkFileFormat::Yaml, id).unwrap())
+            .is_true();
+        assert_that(&remove_bookmark_from_file(&file, BookmarkFileFormat::Yaml, id).unwrap())
+            .is_false();
+        assert_that(&std::fs::read_to_string(&file).unwrap()).is_equal_to("---\n[]\n".to_string());
+    }
+}
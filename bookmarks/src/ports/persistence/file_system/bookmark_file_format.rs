@@ -0,0 +1,64 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BookmarkFileFormat {
+    Yaml,
+    Json,
+    Toml,
+    #[serde(alias = "netscape")]
+    Html,
+    Xbel,
+    #[serde(alias = "md")]
+    Markdown,
+}
+
+impl BookmarkFileFormat {
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "yml" | "yaml" => Some(BookmarkFileFormat::Yaml),
+            "json" => Some(BookmarkFileFormat::Json),
+            "toml" => Some(BookmarkFileFormat::Toml),
+            "html" | "htm" => Some(BookmarkFileFormat::Html),
+            "xbel" => Some(BookmarkFileFormat::Xbel),
+            "md" | "markdown" => Some(BookmarkFileFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Whether files of this format, which are usually other kinds of documents, are only read
+    /// from a directory or pattern whose source names the format.
+    pub(crate) fn is_opt_in(&self) -> bool {
+        matches!(
+            self,
+            BookmarkFileFormat::Html | BookmarkFileFormat::Markdown
+        )
+    }
+
+    pub(crate) fn detect(path: &Path) -> Self {
+        Self::from_path(path).unwrap_or(BookmarkFileFormat::Yaml)
+    }
+
+    pub(crate) fn is_writable(&self) -> bool {
+        !matches!(
+            self,
+            BookmarkFileFormat::Html | BookmarkFileFormat::Markdown
+        )
+    }
+}
+
+impl Display for BookmarkFileFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BookmarkFileFormat::Yaml => "YAML",
+            BookmarkFileFormat::Json => "JSON",
+            BookmarkFileFormat::Toml => "TOML",
+            BookmarkFileFormat::Html => "Netscape HTML",
+            BookmarkFileFormat::Xbel => "XBEL",
+            BookmarkFileFormat::Markdown => "Markdown",
+        };
+        write!(f, "{}", name)
+    }
+}
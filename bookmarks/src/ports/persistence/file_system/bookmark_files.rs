@@ -0,0 +1,518 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use crate::ports::persistence::file_system::bookmark_file_format::BookmarkFileFormat;
+use crate::ports::persistence::file_system::bookmark_source::BookmarkSource;
+use crate::ports::persistence::file_system::markdown::parse_markdown;
+use crate::ports::persistence::file_system::netscape_html::parse_netscape_html;
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+use crate::ports::persistence::file_system::persistence_entry::PersistenceEntry;
+use crate::ports::persistence::file_system::xbel::{parse_xbel, write_xbel, XbelError};
+use crate::ports::persistence::file_system::FileSystemBookmarkRepositoryAdapterError;
+
+pub(super) struct LoadedBookmark {
+    bookmark: PersistenceBookmark,
+    inherited_tags: Vec<String>,
+    group_path: Vec<String>,
+    location: BookmarkLocation,
+}
+
+impl LoadedBookmark {
+    pub(super) fn bookmark(&self) -> &PersistenceBookmark {
+        &self.bookmark
+    }
+
+    pub(super) fn inherited_tags(&self) -> &Vec<String> {
+        &self.inherited_tags
+    }
+
+    pub(super) fn group_path(&self) -> &Vec<String> {
+        &self.group_path
+    }
+
+    pub(super) fn location(&self) -> &BookmarkLocation {
+        &self.location
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BookmarkLocation {
+    file: PathBuf,
+    format: BookmarkFileFormat,
+    entry: usize,
+}
+
+impl BookmarkLocation {
+    pub(super) fn file(&self) -> &Path {
+        self.file.as_path()
+    }
+
+    pub(super) fn format(&self) -> BookmarkFileFormat {
+        self.format
+    }
+}
+
+impl Display for BookmarkLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (entry {})", self.file.display(), self.entry)
+    }
+}
+
+#[derive(Default)]
+pub(super) struct BookmarkFiles {
+    bookmarks: Vec<LoadedBookmark>,
+    files: Vec<PathBuf>,
+    loaded: HashSet<PathBuf>,
+}
+
+impl BookmarkFiles {
+    pub(super) fn load(
+        sources: &[BookmarkSource],
+    ) -> Result<Self, FileSystemBookmarkRepositoryAdapterError> {
+        let mut bookmark_files = BookmarkFiles::default();
+        for source in sources {
+            for file in expand_pattern(source.path(), source.format())? {
+                let format = source
+                    .format()
+                    .unwrap_or_else(|| BookmarkFileFormat::detect(file.as_path()));
+                bookmark_files.load_file(file.as_path(), format, source.tags())?;
+            }
+        }
+        Ok(bookmark_files)
+    }
+
+    pub(super) fn bookmarks(&self) -> &Vec<LoadedBookmark> {
+        &self.bookmarks
+    }
+
+    pub(super) fn files(&self) -> &Vec<PathBuf> {
+        &self.files
+    }
+
+    fn load_file(
+        &mut self,
+        file: &Path,
+        format: BookmarkFileFormat,
+        inherited_tags: &[String],
+    ) -> Result<(), FileSystemBookmarkRepositoryAdapterError> {
+        let canonical_file = file
+            .canonicalize()
+            .map_err(|_| FileSystemBookmarkRepositoryAdapterError::ReadError(file.to_path_buf()))?;
+        if !self.loaded.insert(canonical_file) {
+            return Ok(());
+        }
+        self.files.push(file.to_path_buf());
+
+        let content = std::fs::read(file)
+            .map_err(|_| FileSystemBookmarkRepositoryAdapterError::ReadError(file.to_path_buf()))?;
+        let document = parse_bookmark_file(file, format, content.as_slice()).map_err(|err| {
+            FileSystemBookmarkRepositoryAdapterError::DeserializeError(format!(
+                "{}: {}",
+                file.display(),
+                err
+            ))
+        })?;
+
+        let mut tags = inherited_tags.to_vec();
+        tags.extend(document.tags);
+
+        let mut entry_count = 0;
+        self.add_entries(
+            file,
+            format,
+            document.bookmarks,
+            &tags,
+            &[],
+            &mut entry_count,
+        );
+
+        let file_directory = file.parent().unwrap_or_else(|| Path::new(""));
+        for include in document.include {
+            for included_file in expand_pattern(file_directory.join(include).as_path(), None)? {
+                let format = BookmarkFileFormat::detect(included_file.as_path());
+                self.load_file(included_file.as_path(), format, &tags)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_entries(
+        &mut self,
+        file: &Path,
+        format: BookmarkFileFormat,
+        entries: Vec<PersistenceEntry>,
+        inherited_tags: &[String],
+        group_path: &[String],
+        entry_count: &mut usize,
+    ) {
+        for entry in entries {
+            match entry {
+                PersistenceEntry::Bookmark(bookmark) => {
+                    *entry_count += 1;
+                    self.bookmarks.push(LoadedBookmark {
+                        bookmark,
+                        inherited_tags: inherited_tags.to_vec(),
+                        group_path: group_path.to_vec(),
+                        location: BookmarkLocation {
+                            file: file.to_path_buf(),
+                            format,
+                            entry: *entry_count,
+                        },
+                    });
+                }
+                PersistenceEntry::Group(group) => {
+                    let mut group_tags = inherited_tags.to_vec();
+                    group_tags.extend(group.tags().iter().cloned());
+                    let mut nested_group_path = group_path.to_vec();
+                    nested_group_path.push(group.name().clone());
+                    self.add_entries(
+                        file,
+                        format,
+                        group.into_entries(),
+                        &group_tags,
+                        &nested_group_path,
+                        entry_count,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Lists the files a source path stands for. Directories and patterns only yield files of the
+/// given format, or of any format that is not opt-in when none is given.
+fn expand_pattern(
+    pattern: &Path,
+    format: Option<BookmarkFileFormat>,
+) -> Result<Vec<PathBuf>, FileSystemBookmarkRepositoryAdapterError> {
+    if pattern.is_dir() {
+        let mut files = std::fs::read_dir(pattern)
+            .map_err(|_| {
+                FileSystemBookmarkRepositoryAdapterError::ReadError(pattern.to_path_buf())
+            })?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && is_supported_file(path, format))
+            .collect::<Vec<PathBuf>>();
+        files.sort();
+        return Ok(files);
+    }
+
+    let pattern_text = pattern.to_string_lossy();
+    if !pattern_text.contains(['*', '?', '[']) {
+        return Ok(vec![pattern.to_path_buf()]);
+    }
+
+    let mut files = glob::glob(pattern_text.as_ref())
+        .map_err(|err| FileSystemBookmarkRepositoryAdapterError::InvalidPattern(err.to_string()))?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file() && (format.is_some() || is_supported_file(path, None)))
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+    if files.is_empty() {
+        log::warn!("No bookmark files match {}", pattern.display());
+    }
+    Ok(files)
+}
+
+fn is_supported_file(path: &Path, format: Option<BookmarkFileFormat>) -> bool {
+    match (BookmarkFileFormat::from_path(path), format) {
+        (Some(detected), Some(format)) => detected == format,
+        (Some(detected), None) => !detected.is_opt_in(),
+        (None, _) => false,
+    }
+}
+
+pub(super) fn parse_bookmark_file(
+    file: &Path,
+    format: BookmarkFileFormat,
+    content: &[u8],
+) -> Result<BookmarkFile, BookmarkFileError> {
+    match format {
+        BookmarkFileFormat::Yaml => {
+            if serde_yaml::from_slice::<serde_yaml::Value>(content)?.is_sequence() {
+                Ok(BookmarkFile::from_entries(serde_yaml::from_slice(content)?))
+            } else {
+                Ok(serde_yaml::from_slice(content)?)
+            }
+        }
+        BookmarkFileFormat::Json => {
+            if serde_json::from_slice::<serde_json::Value>(content)?.is_array() {
+                Ok(BookmarkFile::from_entries(serde_json::from_slice(content)?))
+            } else {
+                Ok(serde_json::from_slice(content)?)
+            }
+        }
+        BookmarkFileFormat::Toml => {
+            Ok(datetimes_as_strings(toml::from_slice(content)?).try_into()?)
+        }
+        BookmarkFileFormat::Html => Ok(BookmarkFile::from_entries(parse_netscape_html(
+            String::from_utf8_lossy(content).as_ref(),
+        ))),
+        BookmarkFileFormat::Xbel => Ok(parse_xbel(content)?),
+        BookmarkFileFormat::Markdown => Ok(BookmarkFile::from_entries(parse_markdown(
+            file,
+            String::from_utf8_lossy(content).as_ref(),
+        ))),
+    }
+}
+
+/// Bookmark entries are read through YAML values, which have no datetimes, so native TOML
+/// datetimes are turned into the strings they would be written as in any other format.
+fn datetimes_as_strings(value: toml::Value) -> toml::Value {
+    match value {
+        toml::Value::Datetime(datetime) => toml::Value::String(datetime.to_string()),
+        toml::Value::Array(values) => {
+            toml::Value::Array(values.into_iter().map(datetimes_as_strings).collect())
+        }
+        toml::Value::Table(table) => toml::Value::Table(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, datetimes_as_strings(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Reads a bookmark file to be changed, treating a missing file as empty.
+pub(super) fn read_bookmark_file(
+    file: &Path,
+    format: BookmarkFileFormat,
+) -> Result<BookmarkFile, FileSystemBookmarkRepositoryAdapterError> {
+    if !file.exists() {
+        return Ok(BookmarkFile::default());
+    }
+    let content = std::fs::read(file)
+        .map_err(|_| FileSystemBookmarkRepositoryAdapterError::ReadError(file.to_path_buf()))?;
+    parse_bookmark_file(file, format, content.as_slice()).map_err(|err| {
+        FileSystemBookmarkRepositoryAdapterError::DeserializeError(format!(
+            "{}: {}",
+            file.display(),
+            err
+        ))
+    })
+}
+
+pub(super) fn write_bookmark_file(
+    file: &Path,
+    format: BookmarkFileFormat,
+    document: &BookmarkFile,
+) -> Result<(), FileSystemBookmarkRepositoryAdapterError> {
+    let content = serialize_bookmark_file(format, document)
+        .map_err(|err| FileSystemBookmarkRepositoryAdapterError::SerializeError(err.to_string()))?;
+    std::fs::write(file, content)
+        .map_err(|_| FileSystemBookmarkRepositoryAdapterError::WriteError(file.to_path_buf()))
+}
+
+/// Adds entries to the end of a bookmark file. YAML lists and TOML files get the entries appended
+/// as text so existing entries keep their formatting and comments; other files are rewritten.
+pub(super) fn append_to_bookmark_file(
+    file: &Path,
+    format: BookmarkFileFormat,
+    entries: Vec<PersistenceEntry>,
+) -> Result<(), FileSystemBookmarkRepositoryAdapterError> {
+    let mut document = read_bookmark_file(file, format)?;
+    let entry_count = document.entries().len() + entries.len();
+    let appended = BookmarkFile::from_entries(entries);
+    let serialize_error = |err: BookmarkFileError| {
+        FileSystemBookmarkRepositoryAdapterError::SerializeError(err.to_string())
+    };
+
+    let appended_text = match format {
+        BookmarkFileFormat::Yaml => Some(
+            appended
+                .to_yaml()
+                .map_err(|err| serialize_error(err.into()))?
+                .trim_start_matches("---\n")
+                .to_string(),
+        ),
+        BookmarkFileFormat::Toml => {
+            Some(toml::to_string(&appended).map_err(|err| serialize_error(err.into()))?)
+        }
+        _ => None,
+    };
+    if let Some(appended_text) = appended_text {
+        let mut content = std::fs::read_to_string(file).unwrap_or_default();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        if !content.is_empty() && format == BookmarkFileFormat::Toml {
+            content.push('\n');
+        }
+        content.push_str(&appended_text);
+        let is_appendable = parse_bookmark_file(file, format, content.as_bytes())
+            .is_ok_and(|parsed| parsed.entries().len() == entry_count);
+        if is_appendable {
+            return std::fs::write(file, content).map_err(|_| {
+                FileSystemBookmarkRepositoryAdapterError::WriteError(file.to_path_buf())
+            });
+        }
+    }
+
+    document.bookmarks.extend(appended.bookmarks);
+    write_bookmark_file(file, format, &document)
+}
+
+fn serialize_bookmark_file(
+    format: BookmarkFileFormat,
+    document: &BookmarkFile,
+) -> Result<String, BookmarkFileError> {
+    match format {
+        BookmarkFileFormat::Yaml => Ok(document.to_yaml()?),
+        BookmarkFileFormat::Json => {
+            let json = if document.has_settings() {
+                serde_json::to_string_pretty(document)?
+            } else {
+                serde_json::to_string_pretty(&document.bookmarks)?
+            };
+            Ok(format!("{}\n", json))
+        }
+        BookmarkFileFormat::Toml => Ok(toml::to_string(document)?),
+        BookmarkFileFormat::Xbel => Ok(write_xbel(document)),
+        BookmarkFileFormat::Html | BookmarkFileFormat::Markdown => {
+            Err(BookmarkFileError::ReadOnly(format))
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum BookmarkFileError {
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    TomlDeserialize(#[from] toml::de::Error),
+    #[error(transparent)]
+    TomlSerialize(#[from] toml::ser::Error),
+    #[error(transparent)]
+    Xbel(#[from] XbelError),
+    #[error("{0} bookmark files cannot be written")]
+    ReadOnly(BookmarkFileFormat),
+}
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct BookmarkFile {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    include: Vec<PathBuf>,
+    #[serde(default)]
+    bookmarks: Vec<PersistenceEntry>,
+}
+
+impl BookmarkFile {
+    pub(super) fn new(
+        tags: Vec<String>,
+        include: Vec<PathBuf>,
+        bookmarks: Vec<PersistenceEntry>,
+    ) -> Self {
+        BookmarkFile {
+            tags,
+            include,
+            bookmarks,
+        }
+    }
+
+    fn from_entries(bookmarks: Vec<PersistenceEntry>) -> Self {
+        BookmarkFile {
+            bookmarks,
+            ..BookmarkFile::default()
+        }
+    }
+
+    pub(super) fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub(super) fn include(&self) -> &Vec<PathBuf> {
+        &self.include
+    }
+
+    pub(super) fn entries(&self) -> &Vec<PersistenceEntry> {
+        &self.bookmarks
+    }
+
+    pub(super) fn into_entries(self) -> Vec<PersistenceEntry> {
+        self.bookmarks
+    }
+
+    pub(super) fn entries_mut(&mut self) -> &mut Vec<PersistenceEntry> {
+        &mut self.bookmarks
+    }
+
+    pub(super) fn push(&mut self, entry: PersistenceEntry) {
+        self.bookmarks.push(entry);
+    }
+
+    pub(super) fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        if self.has_settings() {
+            serde_yaml::to_string(self)
+        } else {
+            serde_yaml::to_string(&self.bookmarks)
+        }
+    }
+
+    fn has_settings(&self) -> bool {
+        !self.tags.is_empty() || !self.include.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    const TOML: &str = r#"tags = ["team"]
+include = ["shared.toml"]
+
+[[bookmarks]]
+url = "https://github.com/"
+name = "GitHub"
+
+[[bookmarks]]
+group = "Platform"
+tags = ["platform"]
+
+[[bookmarks.bookmarks]]
+url = "https://grafana.example.com/"
+name = "Grafana"
+description = "Service dashboards"
+tags = ["metrics"]
+aliases = ["gf"]
+created_at = "2020-09-13T12:26:40Z"
+updated_at = "2021-01-01T00:00:00Z"
+"#;
+
+    #[test]
+    fn round_trips_toml_files_with_groups_and_optional_fields() {
+        let file = Path::new("bookmarks.toml");
+        let parsed = parse_bookmark_file(file, BookmarkFileFormat::Toml, TOML.as_bytes()).unwrap();
+
+        let toml = serialize_bookmark_file(BookmarkFileFormat::Toml, &parsed).unwrap();
+
+        assert_that(&toml).is_equal_to(TOML.to_string());
+        assert_that(&parsed.to_yaml().unwrap()).is_equal_to(
+            "---\ntags:\n  - team\ninclude:\n  - shared.toml\nbookmarks:\n  - url: \"https://github.com/\"\n    name: GitHub\n  - group: Platform\n    tags:\n      - platform\n    bookmarks:\n      - url: \"https://grafana.example.com/\"\n        name: Grafana\n        description: Service dashboards\n        tags:\n          - metrics\n        aliases:\n          - gf\n        created_at: \"2020-09-13T12:26:40Z\"\n        updated_at: \"2021-01-01T00:00:00Z\"\n"
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn reads_native_toml_datetimes() {
+        let file = Path::new("bookmarks.toml");
+        let native = TOML
+            .replace(r#""2020-09-13T12:26:40Z""#, "2020-09-13T12:26:40Z")
+            .replace(r#""2021-01-01T00:00:00Z""#, "2021-01-01T00:00:00Z");
+
+        let parsed =
+            parse_bookmark_file(file, BookmarkFileFormat::Toml, native.as_bytes()).unwrap();
+
+        assert_that(&serialize_bookmark_file(BookmarkFileFormat::Toml, &parsed).unwrap())
+            .is_equal_to(TOML.to_string());
+    }
+}
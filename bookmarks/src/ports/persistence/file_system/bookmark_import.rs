@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::domain::bookmark::{normalise_url, Bookmark};
+use crate::ports::persistence::file_system::bookmark_file_format::BookmarkFileFormat;
+use crate::ports::persistence::file_system::bookmark_files::{
+    append_to_bookmark_file, BookmarkFileError,
+};
+use crate::ports::persistence::file_system::bookmarking_services::{
+    parse_linkding_bookmarks, parse_pinboard_bookmarks,
+};
+use crate::ports::persistence::file_system::chromium_bookmarks::parse_chromium_bookmarks;
+use crate::ports::persistence::file_system::csv_bookmarks::{parse_csv_bookmarks, CsvColumns};
+use crate::ports::persistence::file_system::firefox_places::read_firefox_places;
+use crate::ports::persistence::file_system::netscape_html::parse_netscape_html;
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+use crate::ports::persistence::file_system::persistence_entry::{
+    flatten_entries, PersistenceEntry,
+};
+use crate::ports::persistence::file_system::xbel::parse_xbel;
+use crate::ports::persistence::file_system::FileSystemBookmarkRepositoryAdapterError;
+
+#[derive(Debug, Clone)]
+pub(crate) enum BookmarkImportFormat {
+    NetscapeHtml,
+    Chromium,
+    Firefox,
+    Xbel,
+    Csv(CsvColumns),
+    Pinboard,
+    Linkding,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct BookmarkImportReport {
+    added: Vec<String>,
+    skipped: Vec<String>,
+    invalid: Vec<String>,
+}
+
+impl BookmarkImportReport {
+    pub(crate) fn added(&self) -> &Vec<String> {
+        &self.added
+    }
+
+    pub(crate) fn skipped(&self) -> &Vec<String> {
+        &self.skipped
+    }
+
+    pub(crate) fn invalid(&self) -> &Vec<String> {
+        &self.invalid
+    }
+}
+
+pub(crate) fn import_bookmarks(
+    import_file: &Path,
+    format: BookmarkImportFormat,
+    existing_bookmarks: &[Bookmark],
+    target_file: &Path,
+    target_format: BookmarkFileFormat,
+) -> Result<BookmarkImportReport, FileSystemBookmarkRepositoryAdapterError> {
+    if !target_format.is_writable() {
+        return Err(FileSystemBookmarkRepositoryAdapterError::SerializeError(
+            BookmarkFileError::ReadOnly(target_format).to_string(),
+        ));
+    }
+    let (imported_bookmarks, invalid) = read_import_file(import_file, format)?;
+    let existing_urls: HashSet<String> = existing_bookmarks
+        .iter()
+        .map(|bookmark| normalise_url(bookmark.url()))
+        .collect();
+    let mut imported_urls: HashSet<String> = HashSet::new();
+    let mut added_entries = vec![];
+    let mut report = BookmarkImportReport {
+        invalid,
+        ..BookmarkImportReport::default()
+    };
+
+    imported_bookmarks.into_iter().for_each(|bookmark| {
+        let normalised_url = normalise_url(bookmark.url());
+        if existing_urls.contains(&normalised_url) {
+            report
+                .skipped
+                .push(format!("{} (already in collection)", bookmark.url()));
+        } else if !imported_urls.insert(normalised_url) {
+            report
+                .skipped
+                .push(format!("{} (duplicate in import)", bookmark.url()));
+        } else {
+            report.added.push(bookmark.url().to_string());
+            added_entries.push(PersistenceEntry::Bookmark(bookmark));
+        }
+    });
+
+    if !added_entries.is_empty() {
+        append_to_bookmark_file(target_file, target_format, added_entries)?;
+    }
+
+    Ok(report)
+}
+
+fn read_import_file(
+    import_file: &Path,
+    format: BookmarkImportFormat,
+) -> Result<(Vec<PersistenceBookmark>, Vec<String>), FileSystemBookmarkRepositoryAdapterError> {
+    let deserialize_error = |err: String| {
+        FileSystemBookmarkRepositoryAdapterError::DeserializeError(format!(
+            "{}: {}",
+            import_file.display(),
+            err
+        ))
+    };
+
+    let bookmarks = match format {
+        BookmarkImportFormat::NetscapeHtml => {
+            let content = read_file(import_file)?;
+            flatten_entries(
+                parse_netscape_html(String::from_utf8_lossy(&content).as_ref()),
+                &[],
+            )
+        }
+        BookmarkImportFormat::Chromium => parse_chromium_bookmarks(&read_file(import_file)?)
+            .map_err(|err| deserialize_error(err.to_string()))?,
+        BookmarkImportFormat::Firefox => {
+            read_firefox_places(import_file).map_err(|err| deserialize_error(err.to_string()))?
+        }
+        BookmarkImportFormat::Xbel => {
+            let document = parse_xbel(&read_file(import_file)?)
+                .map_err(|err| deserialize_error(err.to_string()))?;
+            let tags = document.tags().clone();
+            flatten_entries(document.into_entries(), &tags)
+        }
+        BookmarkImportFormat::Pinboard => parse_pinboard_bookmarks(&read_file(import_file)?)
+            .map_err(|err| deserialize_error(err.to_string()))?,
+        BookmarkImportFormat::Linkding => parse_linkding_bookmarks(&read_file(import_file)?)
+            .map_err(|err| deserialize_error(err.to_string()))?,
+        BookmarkImportFormat::Csv(columns) => {
+            return parse_csv_bookmarks(&read_file(import_file)?, &columns)
+                .map(|csv_bookmarks| csv_bookmarks.into_parts())
+                .map_err(|err| deserialize_error(err.to_string()));
+        }
+    };
+
+    Ok((bookmarks, vec![]))
+}
+
+fn read_file(file: &Path) -> Result<Vec<u8>, FileSystemBookmarkRepositoryAdapterError> {
+    std::fs::read(file)
+        .map_err(|_| FileSystemBookmarkRepositoryAdapterError::ReadError(file.to_path_buf()))
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+    use url::Url;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn appends_new_bookmarks_without_rewriting_existing_ones() {
+        let directory = tempfile::tempdir().unwrap();
+        let import_file = directory.path().join("Bookmarks");
+        std::fs::write(
+            &import_file,
+            r#"{"roots": {"bookmark_bar": {"type": "folder", "name": "Bookmarks bar", "children": [
+                {"type": "url", "name": "GitHub", "url": "https://www.github.com/#readme"},
+                {"type": "folder", "name": "Docs", "children": [
+                    {"type": "url", "name": "Rust", "url": "https://doc.rust-lang.org/"},
+                    {"type": "url", "name": "Rust again", "url": "https://doc.rust-lang.org"}
+                ]}
+            ]}}}"#,
+        )
+        .unwrap();
+        let target_file = directory.path().join("bookmarks.yml");
+        std::fs::write(
+            &target_file,
+            "- name: GitHub\n  url: https://github.com\n  tags: []\n",
+        )
+        .unwrap();
+        let existing = vec![Bookmark::new(
+            Uuid::new_v4(),
+            Url::parse("https://github.com").unwrap(),
+            "GitHub".to_string(),
+            None,
+            vec![],
+        )];
+
+        let report = import_bookmarks(
+            &import_file,
+            BookmarkImportFormat::Chromium,
+            &existing,
+            &target_file,
+            BookmarkFileFormat::Yaml,
+        )
+        .unwrap();
+
+        assert_that(report.added()).is_equal_to(vec!["https://doc.rust-lang.org/".to_string()]);
+        assert_that(report.skipped()).is_equal_to(vec![
+            "https://www.github.com/#readme (already in collection)".to_string(),
+            "https://doc.rust-lang.org/ (duplicate in import)".to_string(),
+        ]);
+        assert_that(&std::fs::read_to_string(&target_file).unwrap()).is_equal_to(
+            "- name: GitHub\n  url: https://github.com\n  tags: []\n- url: \"https://doc.rust-lang.org/\"\n  name: Rust\n  tags:\n    - Docs\n"
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn writes_back_in_the_format_of_the_target_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let import_file = directory.path().join("bookmarks.html");
+        std::fs::write(
+            &import_file,
+            r#"<DL><p><DT><A HREF="https://doc.rust-lang.org/">Rust</A></DL><p>"#,
+        )
+        .unwrap();
+        let target_file = directory.path().join("bookmarks.json");
+        std::fs::write(
+            &target_file,
+            r#"{"tags": ["team"], "bookmarks": [{"url": "https://github.com/", "name": "GitHub"}]}"#,
+        )
+        .unwrap();
+
+        import_bookmarks(
+            &import_file,
+            BookmarkImportFormat::NetscapeHtml,
+            &[],
+            &target_file,
+            BookmarkFileFormat::Json,
+        )
+        .unwrap();
+
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&target_file).unwrap()).unwrap();
+        assert_that(&written).is_equal_to(serde_json::json!({
+            "tags": ["team"],
+            "bookmarks": [
+                {"url": "https://github.com/", "name": "GitHub"},
+                {"url": "https://doc.rust-lang.org/", "name": "Rust"}
+            ]
+        }));
+    }
+
+    #[test]
+    fn appends_to_toml_files_and_keeps_their_comments() {
+        let directory = tempfile::tempdir().unwrap();
+        let import_file = directory.path().join("bookmarks.html");
+        std::fs::write(
+            &import_file,
+            r#"<DL><p><DT><A HREF="https://doc.rust-lang.org/">Rust</A></DL><p>"#,
+        )
+        .unwrap();
+        let target_file = directory.path().join("bookmarks.toml");
+        std::fs::write(
+            &target_file,
+            "# Team bookmarks\n[[bookmarks]]\nname = 'GitHub'\nurl = 'https://github.com'\n",
+        )
+        .unwrap();
+
+        import_bookmarks(
+            &import_file,
+            BookmarkImportFormat::NetscapeHtml,
+            &[],
+            &target_file,
+            BookmarkFileFormat::Toml,
+        )
+        .unwrap();
+
+        assert_that(&std::fs::read_to_string(&target_file).unwrap()).is_equal_to(
+            "# Team bookmarks\n[[bookmarks]]\nname = 'GitHub'\nurl = 'https://github.com'\n\n[[bookmarks]]\nurl = \"https://doc.rust-lang.org/\"\nname = \"Rust\"\n"
+                .to_string(),
+        );
+    }
+}
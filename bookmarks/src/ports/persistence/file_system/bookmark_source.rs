@@ -0,0 +1,152 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::ports::persistence::file_system::bookmark_file_format::BookmarkFileFormat;
+use crate::ports::persistence::file_system::remote_bookmark_file::RemoteBookmarkFile;
+
+const REMOTE_CACHE_DIRECTORY: &str = ".bookmarks-cache";
+
+/// A bookmark file, directory or glob pattern. For a remote source, `path` is the local copy of
+/// the remote file.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(from = "BookmarkSourceDefinition")]
+pub(crate) struct BookmarkSource {
+    path: PathBuf,
+    tags: Vec<String>,
+    format: Option<BookmarkFileFormat>,
+    remote: Option<RemoteBookmarkFile>,
+}
+
+impl BookmarkSource {
+    pub(crate) fn new<P: AsRef<Path>>(path: P, tags: Vec<String>) -> Self {
+        BookmarkSource {
+            path: path.as_ref().to_path_buf(),
+            tags,
+            format: None,
+            remote: None,
+        }
+    }
+
+    /// A source fetched from `url`, kept in `cache` or a file named after the url.
+    pub(crate) fn remote(
+        url: Url,
+        cache: Option<PathBuf>,
+        poll_interval: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Self {
+        let path = cache.unwrap_or_else(|| default_cache_file(&url));
+        BookmarkSource {
+            path,
+            tags,
+            format: None,
+            remote: Some(RemoteBookmarkFile::new(url, poll_interval)),
+        }
+    }
+
+    pub(crate) fn with_format(self, format: Option<BookmarkFileFormat>) -> Self {
+        BookmarkSource { format, ..self }
+    }
+
+    pub(crate) fn resolve_path(self, base_directory: &Path) -> Self {
+        BookmarkSource {
+            path: base_directory.join(self.path),
+            ..self
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    pub(crate) fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub(crate) fn format(&self) -> Option<BookmarkFileFormat> {
+        self.format
+    }
+
+    pub(crate) fn remote_file(&self) -> Option<&RemoteBookmarkFile> {
+        self.remote.as_ref()
+    }
+}
+
+fn default_cache_file(url: &Url) -> PathBuf {
+    let extension = Path::new(url.path())
+        .extension()
+        .filter(|_| BookmarkFileFormat::from_path(Path::new(url.path())).is_some())
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("yml");
+    let hash = Sha256::digest(url.as_str().as_bytes())
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    Path::new(REMOTE_CACHE_DIRECTORY).join(format!("{}.{}", hash, extension))
+}
+
+/// The first source that is a single file in a format bookmarks can be written back to.
+pub(crate) fn writable_bookmark_file(
+    sources: &[BookmarkSource],
+) -> Option<(&BookmarkSource, BookmarkFileFormat)> {
+    sources
+        .iter()
+        .filter(|source| source.remote.is_none() && source.path().is_file())
+        .find_map(|source| {
+            let format = source
+                .format()
+                .or_else(|| BookmarkFileFormat::from_path(source.path()))?;
+            format.is_writable().then_some((source, format))
+        })
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum BookmarkSourceDefinition {
+    Path(PathBuf),
+    Remote {
+        url: Url,
+        #[serde(default)]
+        cache: Option<PathBuf>,
+        #[serde(default)]
+        poll_interval_seconds: Option<u64>,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        format: Option<BookmarkFileFormat>,
+    },
+    Tagged {
+        path: PathBuf,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        format: Option<BookmarkFileFormat>,
+    },
+}
+
+impl From<BookmarkSourceDefinition> for BookmarkSource {
+    fn from(definition: BookmarkSourceDefinition) -> Self {
+        match definition {
+            BookmarkSourceDefinition::Path(path) => BookmarkSource::new(path, vec![]),
+            BookmarkSourceDefinition::Remote {
+                url,
+                cache,
+                poll_interval_seconds,
+                tags,
+                format,
+            } => BookmarkSource::remote(
+                url,
+                cache,
+                poll_interval_seconds.map(Duration::from_secs),
+                tags,
+            )
+            .with_format(format),
+            BookmarkSourceDefinition::Tagged { path, tags, format } => {
+                BookmarkSource::new(path, tags).with_format(format)
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use chrono::{DateTime, Utc};
+use url::Url;
+
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+
+const UNREAD_TAG: &str = "unread";
+const PRIVATE_TAG: &str = "private";
+
+pub(super) fn parse_pinboard_bookmarks(
+    content: &[u8],
+) -> Result<Vec<PersistenceBookmark>, serde_json::Error> {
+    let posts: Vec<PinboardPost> = serde_json::from_slice(content)?;
+    Ok(posts
+        .into_iter()
+        .filter_map(|post| {
+            let mut tags = split_tags(&post.tags);
+            add_status_tags(&mut tags, post.shared == "no", post.toread == "yes");
+            bookmark(
+                &post.href,
+                post.description,
+                post.extended,
+                tags,
+                post.time.as_deref(),
+            )
+        })
+        .collect())
+}
+
+pub(super) fn parse_linkding_bookmarks(
+    content: &[u8],
+) -> Result<Vec<PersistenceBookmark>, serde_json::Error> {
+    let bookmarks = match serde_json::from_slice(content)? {
+        LinkdingExport::Page { results } => results,
+        LinkdingExport::List(bookmarks) => bookmarks,
+    };
+    Ok(bookmarks
+        .into_iter()
+        .filter_map(|bookmark_entry| {
+            let mut tags = match bookmark_entry.tag_names {
+                Some(tag_names) => tag_names,
+                None => split_tags(&bookmark_entry.tags.unwrap_or_default()),
+            };
+            add_status_tags(
+                &mut tags,
+                !bookmark_entry.shared,
+                bookmark_entry.unread || bookmark_entry.is_unread,
+            );
+            let title = if bookmark_entry.title.is_empty() {
+                bookmark_entry.website_title.unwrap_or_default()
+            } else {
+                bookmark_entry.title
+            };
+            let description = if bookmark_entry.notes.is_empty() {
+                bookmark_entry.description
+            } else {
+                bookmark_entry.notes
+            };
+            bookmark(
+                &bookmark_entry.url,
+                title,
+                description,
+                tags,
+                bookmark_entry.date_added.as_deref(),
+            )
+        })
+        .collect())
+}
+
+fn bookmark(
+    href: &str,
+    title: String,
+    description: String,
+    tags: Vec<String>,
+    created_at: Option<&str>,
+) -> Option<PersistenceBookmark> {
+    let url = match Url::parse(href) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => url,
+        _ => {
+            log::debug!("Skipping bookmark with unsupported url: {}", href);
+            return None;
+        }
+    };
+    let name = if title.trim().is_empty() {
+        url.to_string()
+    } else {
+        title.trim().to_string()
+    };
+    let description = Some(description.trim().to_string()).filter(|text| !text.is_empty());
+    let created_at = created_at
+        .and_then(|created_at| DateTime::parse_from_rfc3339(created_at).ok())
+        .map(|created_at| created_at.with_timezone(&Utc));
+
+    Some(PersistenceBookmark::new(
+        url,
+        name,
+        description,
+        tags,
+        vec![],
+        created_at,
+    ))
+}
+
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split_whitespace().map(str::to_string).collect()
+}
+
+fn add_status_tags(tags: &mut Vec<String>, private: bool, unread: bool) {
+    for (applies, tag) in [(private, PRIVATE_TAG), (unread, UNREAD_TAG)].iter() {
+        if *applies && !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct PinboardPost {
+    href: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    extended: String,
+    #[serde(default)]
+    tags: String,
+    time: Option<String>,
+    #[serde(default)]
+    shared: String,
+    #[serde(default)]
+    toread: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum LinkdingExport {
+    Page { results: Vec<LinkdingBookmark> },
+    List(Vec<LinkdingBookmark>),
+}
+
+#[derive(serde::Deserialize)]
+struct LinkdingBookmark {
+    url: String,
+    #[serde(default)]
+    title: String,
+    website_title: Option<String>,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    notes: String,
+    tag_names: Option<Vec<String>>,
+    tags: Option<String>,
+    date_added: Option<String>,
+    #[serde(default = "default_shared")]
+    shared: bool,
+    #[serde(default)]
+    unread: bool,
+    #[serde(default)]
+    is_unread: bool,
+}
+
+fn default_shared() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn maps_pinboard_posts() {
+        let export = r#"[
+            {"href": "https://grafana.example.com/", "description": "Grafana", "extended": "Dashboards",
+             "meta": "abc", "hash": "def", "time": "2020-09-13T12:26:40Z", "shared": "no", "toread": "yes",
+             "tags": "metrics ops"},
+            {"href": "javascript:void(0)", "description": "Bookmarklet", "shared": "yes", "toread": "no", "tags": ""}
+        ]"#;
+
+        let bookmarks = parse_pinboard_bookmarks(export.as_bytes()).unwrap();
+
+        assert_that(&bookmarks).has_length(1);
+        assert_that(bookmarks[0].name()).is_equal_to("Grafana".to_string());
+        assert_that(&bookmarks[0].description()).is_equal_to(Some(&"Dashboards".to_string()));
+        assert_that(bookmarks[0].tags()).is_equal_to(vec![
+            "metrics".to_string(),
+            "ops".to_string(),
+            "private".to_string(),
+            "unread".to_string(),
+        ]);
+        assert_that(&bookmarks[0].created_at())
+            .is_equal_to(Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap()));
+    }
+
+    #[test]
+    fn maps_linkding_bookmarks() {
+        let export = r#"{"count": 1, "next": null, "previous": null, "results": [
+            {"id": 1, "url": "https://wiki.example.com", "title": "", "website_title": "Team wiki",
+             "description": "", "notes": "Start here", "tag_names": ["docs"],
+             "date_added": "2020-09-13T12:26:40.123456Z", "shared": true, "unread": false}
+        ]}"#;
+
+        let bookmarks = parse_linkding_bookmarks(export.as_bytes()).unwrap();
+
+        assert_that(&bookmarks).has_length(1);
+        assert_that(bookmarks[0].name()).is_equal_to("Team wiki".to_string());
+        assert_that(&bookmarks[0].description()).is_equal_to(Some(&"Start here".to_string()));
+        assert_that(bookmarks[0].tags()).is_equal_to(vec!["docs".to_string()]);
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, TimeZone, Utc};
+use url::Url;
+
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+
+const WINDOWS_TO_UNIX_EPOCH_SECONDS: i64 = 11_644_473_600;
+
+pub(super) fn parse_chromium_bookmarks(
+    content: &[u8],
+) -> Result<Vec<PersistenceBookmark>, serde_json::Error> {
+    let file: ChromiumBookmarksFile = serde_json::from_slice(content)?;
+    let mut bookmarks = vec![];
+    [file.roots.bookmark_bar, file.roots.other, file.roots.synced]
+        .iter()
+        .flatten()
+        .for_each(|root| collect_bookmarks(&root.children, &[], &mut bookmarks));
+    Ok(bookmarks)
+}
+
+fn collect_bookmarks(
+    nodes: &[ChromiumNode],
+    folders: &[String],
+    bookmarks: &mut Vec<PersistenceBookmark>,
+) {
+    nodes.iter().for_each(|node| match node.node_type.as_str() {
+        "folder" => {
+            let mut nested_folders = folders.to_vec();
+            nested_folders.push(node.name.clone());
+            collect_bookmarks(&node.children, &nested_folders, bookmarks);
+        }
+        "url" => match node.url.as_deref().map(Url::parse) {
+            Some(Ok(url)) if url.scheme() == "http" || url.scheme() == "https" => {
+                let name = if node.name.is_empty() {
+                    url.to_string()
+                } else {
+                    node.name.clone()
+                };
+                bookmarks.push(PersistenceBookmark::new(
+                    url,
+                    name,
+                    None,
+                    folders.to_vec(),
+                    vec![],
+                    node.date_added.as_deref().and_then(chromium_timestamp),
+                ));
+            }
+            _ => log::debug!("Skipping bookmark with unsupported url: {:?}", node.url),
+        },
+        _ => {}
+    });
+}
+
+fn chromium_timestamp(microseconds: &str) -> Option<DateTime<Utc>> {
+    let microseconds: i64 = microseconds.parse().ok()?;
+    if microseconds == 0 {
+        return None;
+    }
+    Utc.timestamp_opt(microseconds / 1_000_000 - WINDOWS_TO_UNIX_EPOCH_SECONDS, 0)
+        .single()
+}
+
+#[derive(serde::Deserialize)]
+struct ChromiumBookmarksFile {
+    roots: ChromiumRoots,
+}
+
+#[derive(serde::Deserialize)]
+struct ChromiumRoots {
+    bookmark_bar: Option<ChromiumNode>,
+    other: Option<ChromiumNode>,
+    synced: Option<ChromiumNode>,
+}
+
+#[derive(serde::Deserialize)]
+struct ChromiumNode {
+    #[serde(rename = "type")]
+    node_type: String,
+    #[serde(default)]
+    name: String,
+    url: Option<String>,
+    date_added: Option<String>,
+    #[serde(default)]
+    children: Vec<ChromiumNode>,
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn maps_folder_hierarchy_to_tags() {
+        let content = r#"{
+            "checksum": "abc",
+            "roots": {
+                "bookmark_bar": {
+                    "type": "folder", "name": "Bookmarks bar", "date_added": "0",
+                    "children": [
+                        { "type": "folder", "name": "Work", "children": [
+                            { "type": "folder", "name": "Platform", "children": [
+                                { "type": "url", "name": "Grafana", "url": "https://grafana.example.com/",
+                                  "date_added": "13245202800000000" }
+                            ] }
+                        ] },
+                        { "type": "url", "name": "Settings", "url": "chrome://settings" }
+                    ]
+                },
+                "other": { "type": "folder", "name": "Other bookmarks", "children": [
+                    { "type": "url", "name": "GitHub", "url": "https://github.com/" }
+                ] }
+            },
+            "version": 1
+        }"#;
+
+        let bookmarks = parse_chromium_bookmarks(content.as_bytes()).unwrap();
+
+        assert_that(&bookmarks).has_length(2);
+        assert_that(bookmarks[0].name()).is_equal_to("Grafana".to_string());
+        assert_that(bookmarks[0].tags())
+            .is_equal_to(vec!["Work".to_string(), "Platform".to_string()]);
+        assert_that(&bookmarks[0].created_at())
+            .is_equal_to(Some(Utc.ymd(2020, 9, 21).and_hms(23, 0, 0)));
+        assert_that(bookmarks[1].tags()).is_empty();
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use csv::StringRecord;
+use url::Url;
+
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct CsvColumns {
+    url: String,
+    name: String,
+    description: String,
+    tags: String,
+    tag_separator: String,
+    aliases: String,
+    created_at: String,
+    date_format: Option<String>,
+}
+
+impl Default for CsvColumns {
+    fn default() -> Self {
+        CsvColumns {
+            url: "url".to_string(),
+            name: "name".to_string(),
+            description: "description".to_string(),
+            tags: "tags".to_string(),
+            tag_separator: ",".to_string(),
+            aliases: "aliases".to_string(),
+            created_at: "created_at".to_string(),
+            date_format: None,
+        }
+    }
+}
+
+impl CsvColumns {
+    pub(crate) fn headers(&self) -> [&str; 6] {
+        [
+            self.url.as_str(),
+            self.name.as_str(),
+            self.description.as_str(),
+            self.tags.as_str(),
+            self.aliases.as_str(),
+            self.created_at.as_str(),
+        ]
+    }
+
+    pub(crate) fn tag_separator(&self) -> &str {
+        self.tag_separator.as_str()
+    }
+
+    pub(crate) fn format_date(&self, date: &DateTime<Utc>) -> String {
+        match &self.date_format {
+            Some(date_format) => date.format(date_format).to_string(),
+            None => date.to_rfc3339(),
+        }
+    }
+
+    fn parse_date(&self, date: &str) -> Option<DateTime<Utc>> {
+        if let Some(date_format) = &self.date_format {
+            return NaiveDateTime::parse_from_str(date, date_format)
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(date, date_format)
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+                .map(|date| Utc.from_utc_datetime(&date));
+        }
+        if let Ok(seconds) = date.parse::<i64>() {
+            return Utc.timestamp_opt(seconds, 0).single();
+        }
+        DateTime::parse_from_rfc3339(date)
+            .map(|date| date.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    .map(|date| Utc.from_utc_datetime(&date))
+            })
+    }
+
+    fn split(&self, values: &str) -> Vec<String> {
+        values
+            .split(self.tag_separator.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct CsvBookmarks {
+    bookmarks: Vec<PersistenceBookmark>,
+    invalid_rows: Vec<String>,
+}
+
+impl CsvBookmarks {
+    pub(super) fn into_parts(self) -> (Vec<PersistenceBookmark>, Vec<String>) {
+        (self.bookmarks, self.invalid_rows)
+    }
+}
+
+pub(super) fn parse_csv_bookmarks(
+    content: &[u8],
+    columns: &CsvColumns,
+) -> Result<CsvBookmarks, CsvBookmarksError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content);
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+    };
+    let row_columns = RowColumns {
+        url: column(&columns.url)
+            .ok_or_else(|| CsvBookmarksError::MissingColumn(columns.url.clone()))?,
+        name: column(&columns.name),
+        description: column(&columns.description),
+        tags: column(&columns.tags),
+        aliases: column(&columns.aliases),
+        created_at: column(&columns.created_at),
+    };
+
+    let mut csv_bookmarks = CsvBookmarks::default();
+    for record in reader.records() {
+        let row = record.map_err(|err| {
+            let line = err.position().map_or(0, |position| position.line());
+            format!("line {}: {}", line, err)
+        });
+        match row.and_then(|record| {
+            let line = record.position().map_or(0, |position| position.line());
+            row_columns
+                .bookmark(&record, columns)
+                .map_err(|reason| format!("line {}: {}", line, reason))
+        }) {
+            Ok(bookmark) => csv_bookmarks.bookmarks.push(bookmark),
+            Err(invalid_row) => csv_bookmarks.invalid_rows.push(invalid_row),
+        }
+    }
+
+    Ok(csv_bookmarks)
+}
+
+struct RowColumns {
+    url: usize,
+    name: Option<usize>,
+    description: Option<usize>,
+    tags: Option<usize>,
+    aliases: Option<usize>,
+    created_at: Option<usize>,
+}
+
+impl RowColumns {
+    fn bookmark(
+        &self,
+        record: &StringRecord,
+        columns: &CsvColumns,
+    ) -> Result<PersistenceBookmark, String> {
+        let value = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let url = match value(Some(self.url)) {
+            Some(url) => Url::parse(url).map_err(|err| format!("invalid url {}: {}", url, err))?,
+            None => return Err("missing url".to_string()),
+        };
+        let created_at = match value(self.created_at) {
+            Some(date) => Some(
+                columns
+                    .parse_date(date)
+                    .ok_or_else(|| format!("invalid date {}", date))?,
+            ),
+            None => None,
+        };
+        let name = value(self.name)
+            .map(str::to_string)
+            .unwrap_or_else(|| url.to_string());
+
+        Ok(PersistenceBookmark::new(
+            url,
+            name,
+            value(self.description).map(str::to_string),
+            value(self.tags)
+                .map(|tags| columns.split(tags))
+                .unwrap_or_default(),
+            value(self.aliases)
+                .map(|aliases| columns.split(aliases))
+                .unwrap_or_default(),
+            created_at,
+        ))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum CsvBookmarksError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error("Missing column: {0}")]
+    MissingColumn(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn maps_columns_and_reports_invalid_rows_by_line() {
+        let columns: CsvColumns = serde_yaml::from_str(
+            "url: URL\nname: Title\ndescription: Note\ntags: Tags\ntag_separator: \"|\"\ncreated_at: Added\n",
+        )
+        .unwrap();
+        let content = "Title,URL,Note,Tags,Added\n\
+                       Grafana,https://grafana.example.com,Dashboards,metrics|ops,1600000000\n\
+                       Broken,not a url,,,\n\
+                       Wiki,https://wiki.example.com,,,2021-03-04\n\
+                       Late,https://late.example.com,,,yesterday\n";
+
+        let (bookmarks, invalid_rows) = parse_csv_bookmarks(content.as_bytes(), &columns)
+            .unwrap()
+            .into_parts();
+
+        assert_that(&bookmarks).has_length(2);
+        assert_that(bookmarks[0].tags())
+            .is_equal_to(vec!["metrics".to_string(), "ops".to_string()]);
+        assert_that(&bookmarks[0].description()).is_equal_to(Some(&"Dashboards".to_string()));
+        assert_that(&bookmarks[1].created_at())
+            .is_equal_to(Some(Utc.timestamp_opt(1_614_816_000, 0).unwrap()));
+        assert_that(&invalid_rows).is_equal_to(vec![
+            "line 3: invalid url not a url: relative URL without a base".to_string(),
+            "line 5: invalid date yesterday".to_string(),
+        ]);
+    }
+}
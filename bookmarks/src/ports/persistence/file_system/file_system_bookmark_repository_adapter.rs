@@ -1,78 +1,247 @@
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::sync::RwLock;
 
 use uuid::Uuid;
 
-use crate::domain::bookmark::{Bookmark, BookmarkRepository, BookmarkRepositoryError};
+use crate::domain::bookmark::{
+    Bookmark, BookmarkChange, BookmarkRepository, BookmarkRepositoryError, Editor,
+};
+use crate::domain::synonyms::Synonyms;
+use crate::ports::persistence::file_system::bookmark_edit::{
+    remove_bookmark_from_file, save_bookmark_to_file,
+};
+use crate::ports::persistence::file_system::bookmark_files::{
+    BookmarkFiles, BookmarkLocation, LoadedBookmark,
+};
 use crate::ports::persistence::file_system::memory_bookmark::MemoryBookmark;
-use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+use crate::ports::persistence::file_system::{
+    refresh_remote_source, writable_bookmark_file, BookmarkSource,
+};
 
 pub(crate) struct FileSystemBookmarkRepositoryAdapter {
-    store_file_path: PathBuf,
-    inner: Option<HashMap<Uuid, MemoryBookmark>>,
+    sources: Vec<BookmarkSource>,
+    synonyms: Synonyms,
+    loaded: RwLock<Option<LoadedBookmarks>>,
+}
+
+/// The bookmarks as last read from their files, which are read again after every change.
+struct LoadedBookmarks {
+    bookmarks: HashMap<Uuid, MemoryBookmark>,
+    origins: HashMap<Uuid, BookmarkOrigin>,
+    files: Vec<PathBuf>,
+}
+
+/// Where a loaded bookmark is defined, so changes to it can be written back there.
+struct BookmarkOrigin {
+    location: BookmarkLocation,
+    inherited_tags: Vec<String>,
 }
 
 impl FileSystemBookmarkRepositoryAdapter {
-    pub(crate) fn new<P: AsRef<Path>>(store_file_path: P) -> Self {
+    pub(crate) fn new(sources: Vec<BookmarkSource>, synonyms: Synonyms) -> Self {
         FileSystemBookmarkRepositoryAdapter {
-            store_file_path: store_file_path.as_ref().to_path_buf(),
-            inner: None,
+            sources,
+            synonyms,
+            loaded: RwLock::new(None),
         }
     }
 
-    fn inner(
-        &self,
-    ) -> Result<&HashMap<Uuid, MemoryBookmark>, FileSystemBookmarkRepositoryAdapterError> {
-        self.inner
+    pub(crate) fn has_sources(&self) -> bool {
+        !self.sources.is_empty()
+    }
+
+    pub(crate) fn files(&self) -> Vec<PathBuf> {
+        self.read(|loaded| loaded.files.clone()).unwrap_or_default()
+    }
+
+    fn read<T, F>(&self, operation: F) -> Result<T, FileSystemBookmarkRepositoryAdapterError>
+    where
+        F: FnOnce(&LoadedBookmarks) -> T,
+    {
+        self.loaded
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
             .as_ref()
+            .map(operation)
             .ok_or(FileSystemBookmarkRepositoryAdapterError::NotInitialised)
     }
 
     pub(crate) fn initialise(&mut self) -> Result<(), FileSystemBookmarkRepositoryAdapterError> {
-        let content = std::fs::read(self.store_file_path.as_path())
-            .map_err(|_| FileSystemBookmarkRepositoryAdapterError::ConfigError)?;
-        let data: HashMap<Uuid, MemoryBookmark> =
-            serde_yaml::from_slice::<Vec<PersistenceBookmark>>(content.as_slice())
-                .map_err(|_| FileSystemBookmarkRepositoryAdapterError::DeserializeError)?
-                .iter()
-                .map(from_persistence_bookmark)
-                .map(|bookmark| (bookmark.id(), bookmark))
-                .collect();
-        self.inner = Some(data);
+        self.fetch_missing_remote_sources()?;
+        self.load()
+    }
+
+    /// Reads the bookmark files again, using the cached copies of remote sources.
+    pub(crate) fn load(&self) -> Result<(), FileSystemBookmarkRepositoryAdapterError> {
+        let bookmark_files = BookmarkFiles::load(self.sources.as_slice())?;
+        let mut data: HashMap<Uuid, MemoryBookmark> = HashMap::new();
+        let mut locations: HashMap<Uuid, &BookmarkLocation> = HashMap::new();
+        let mut duplicates: Vec<String> = vec![];
+
+        bookmark_files
+            .bookmarks()
+            .iter()
+            .for_each(|loaded_bookmark| {
+                let bookmark = from_loaded_bookmark(loaded_bookmark, &self.synonyms);
+                if let Some(first_location) = locations.get(&bookmark.id()) {
+                    duplicates.push(format!(
+                        "{} is defined in both {} and {}",
+                        bookmark.url(),
+                        first_location,
+                        loaded_bookmark.location()
+                    ));
+                    return;
+                }
+                locations.insert(bookmark.id(), loaded_bookmark.location());
+                data.insert(bookmark.id(), bookmark);
+            });
+
+        if !duplicates.is_empty() {
+            return Err(
+                FileSystemBookmarkRepositoryAdapterError::DuplicateBookmarks(duplicates.join("; ")),
+            );
+        }
+        let origins = bookmark_files
+            .bookmarks()
+            .iter()
+            .map(|loaded_bookmark| {
+                (
+                    Bookmark::id_for(loaded_bookmark.bookmark().url()),
+                    BookmarkOrigin {
+                        location: loaded_bookmark.location().clone(),
+                        inherited_tags: loaded_bookmark.inherited_tags().clone(),
+                    },
+                )
+            })
+            .collect();
+        *self
+            .loaded
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(LoadedBookmarks {
+            bookmarks: data,
+            origins,
+            files: bookmark_files.files().clone(),
+        });
         Ok(())
     }
+
+    /// Remote sources are read from their cached copy, which is only fetched here when there is
+    /// none yet. Keeping it up to date is left to whoever polls the remote sources.
+    fn fetch_missing_remote_sources(&self) -> Result<(), FileSystemBookmarkRepositoryAdapterError> {
+        self.sources
+            .iter()
+            .filter(|source| source.remote_file().is_some() && !source.path().is_file())
+            .try_for_each(|source| {
+                refresh_remote_source(source).map(|_| ()).map_err(|err| {
+                    FileSystemBookmarkRepositoryAdapterError::RemoteError(err.to_string())
+                })
+            })
+    }
 }
 
 impl BookmarkRepository for FileSystemBookmarkRepositoryAdapter {
     fn get_all(&self) -> Result<Vec<Bookmark>, BookmarkRepositoryError> {
-        Ok(self.inner()?.values().map(Bookmark::from).collect())
+        Ok(self.read(|loaded| loaded.bookmarks.values().map(Bookmark::from).collect())?)
     }
 
     fn get(&self, id: Uuid) -> Result<Bookmark, BookmarkRepositoryError> {
-        let bookmark = self
-            .inner()?
-            .get(&id)
+        self.read(|loaded| loaded.bookmarks.get(&id).map(Bookmark::from))?
+            .ok_or(BookmarkRepositoryError::BookmarkNotFound(id))
+    }
+
+    fn save(
+        &self,
+        bookmark: &Bookmark,
+        _editor: &Editor,
+    ) -> Result<BookmarkChange, BookmarkRepositoryError> {
+        let (file, format, inherited_tags) = self
+            .read(|loaded| match loaded.origins.get(&bookmark.id()) {
+                Some(origin) => Some((
+                    origin.location.file().to_path_buf(),
+                    origin.location.format(),
+                    origin.inherited_tags.clone(),
+                )),
+                None => writable_bookmark_file(&self.sources).map(|(source, format)| {
+                    (source.path().to_path_buf(), format, source.tags().clone())
+                }),
+            })?
+            .ok_or_else(|| {
+                BookmarkRepositoryError::ReadOnly(
+                    "no bookmark source is a writable file".to_string(),
+                )
+            })?;
+        if !format.is_writable() {
+            return Err(BookmarkRepositoryError::ReadOnly(format!(
+                "{} is a read-only {} file",
+                file.display(),
+                format
+            )));
+        }
+        let change = save_bookmark_to_file(&file, format, bookmark, &inherited_tags)?;
+        self.load()?;
+        Ok(change)
+    }
+
+    fn remove(&self, id: Uuid, _editor: &Editor) -> Result<Bookmark, BookmarkRepositoryError> {
+        let bookmark = self.get(id)?;
+        let location = self
+            .read(|loaded| {
+                loaded
+                    .origins
+                    .get(&id)
+                    .map(|origin| origin.location.clone())
+            })?
             .ok_or(BookmarkRepositoryError::BookmarkNotFound(id))?;
-        Ok(bookmark.into())
+        if !location.format().is_writable() {
+            return Err(BookmarkRepositoryError::ReadOnly(format!(
+                "{} is a read-only {} file",
+                location.file().display(),
+                location.format()
+            )));
+        }
+        if !remove_bookmark_from_file(location.file(), location.format(), id)? {
+            return Err(BookmarkRepositoryError::BookmarkNotFound(id));
+        }
+        self.load()?;
+        Ok(bookmark)
     }
 }
 
-fn from_persistence_bookmark(bookmark: &PersistenceBookmark) -> MemoryBookmark {
+fn from_loaded_bookmark(loaded_bookmark: &LoadedBookmark, synonyms: &Synonyms) -> MemoryBookmark {
+    let bookmark = loaded_bookmark.bookmark();
+    let mut tags = loaded_bookmark.inherited_tags().clone();
+    tags.extend(bookmark.tags().iter().cloned());
+
     MemoryBookmark::new(
-        Uuid::new_v4(),
+        Bookmark::id_for(bookmark.url()),
         bookmark.url().clone(),
         bookmark.name().clone(),
         bookmark.description().cloned(),
-        bookmark.tags().clone(),
+        synonyms.normalise_tags(&tags),
     )
+    .with_aliases(bookmark.aliases().clone())
+    .with_group_path(loaded_bookmark.group_path().clone())
+    .with_created_at(bookmark.created_at())
+    .with_updated_at(bookmark.updated_at())
 }
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum FileSystemBookmarkRepositoryAdapterError {
-    #[error("Could not deserialize Bookmark")]
-    DeserializeError,
-    #[error("Error with repository configuration")]
-    ConfigError,
+    #[error("Could not deserialize Bookmark: {0}")]
+    DeserializeError(String),
+    #[error("Could not serialize bookmarks: {0}")]
+    SerializeError(String),
+    #[error("Could not read bookmarks from {0}")]
+    ReadError(PathBuf),
+    #[error("Could not write bookmarks to {0}")]
+    WriteError(PathBuf),
+    #[error("Invalid bookmark file pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Duplicate bookmarks found: {0}")]
+    DuplicateBookmarks(String),
+    #[error("Could not load remote bookmarks: {0}")]
+    RemoteError(String),
     #[error("Repository has not been initialised")]
     NotInitialised,
 }
@@ -80,11 +249,289 @@ pub(crate) enum FileSystemBookmarkRepositoryAdapterError {
 impl From<FileSystemBookmarkRepositoryAdapterError> for BookmarkRepositoryError {
     fn from(err: FileSystemBookmarkRepositoryAdapterError) -> Self {
         match err {
-            FileSystemBookmarkRepositoryAdapterError::DeserializeError
+            FileSystemBookmarkRepositoryAdapterError::DeserializeError(_)
+            | FileSystemBookmarkRepositoryAdapterError::SerializeError(_)
             | FileSystemBookmarkRepositoryAdapterError::NotInitialised
-            | FileSystemBookmarkRepositoryAdapterError::ConfigError => {
+            | FileSystemBookmarkRepositoryAdapterError::ReadError(_)
+            | FileSystemBookmarkRepositoryAdapterError::WriteError(_)
+            | FileSystemBookmarkRepositoryAdapterError::InvalidPattern(_)
+            | FileSystemBookmarkRepositoryAdapterError::DuplicateBookmarks(_)
+            | FileSystemBookmarkRepositoryAdapterError::RemoteError(_) => {
                 BookmarkRepositoryError::Unexpected(format!("{}", err))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use crate::ports::persistence::file_system::BookmarkFileFormat;
+
+    use super::*;
+
+    fn write_file(directory: &std::path::Path, name: &str, content: &str) {
+        std::fs::write(directory.join(name), content).unwrap();
+    }
+
+    fn tags_of(bookmarks: &[Bookmark], name: &str) -> Vec<String> {
+        bookmarks
+            .iter()
+            .find(|bookmark| bookmark.name() == name)
+            .unwrap()
+            .tags()
+            .clone()
+    }
+
+    #[test]
+    fn serves_saved_and_removed_bookmarks_without_initialising_again() {
+        let directory = tempfile::tempdir().unwrap();
+        write_file(
+            directory.path(),
+            "platform.yml",
+            "tags: [platform]\nbookmarks:\n  - name: Grafana\n    url: https://grafana.example.com\n",
+        );
+        let mut repository = FileSystemBookmarkRepositoryAdapter::new(
+            vec![BookmarkSource::new(
+                directory.path().join("platform.yml"),
+                vec![],
+            )],
+            Synonyms::default(),
+        );
+        repository.initialise().unwrap();
+        let editor = Editor::new("Ada".to_string(), None);
+        let wiki_url = url::Url::parse("https://wiki.example.com").unwrap();
+        let grafana_url = url::Url::parse("https://grafana.example.com").unwrap();
+
+        repository
+            .save(
+                &Bookmark::new(
+                    Bookmark::id_for(&wiki_url),
+                    wiki_url,
+                    "Wiki".to_string(),
+                    None,
+                    vec!["docs".to_string()],
+                ),
+                &editor,
+            )
+            .unwrap();
+        repository
+            .remove(Bookmark::id_for(&grafana_url), &editor)
+            .unwrap();
+
+        let bookmarks = repository.get_all().unwrap();
+        assert_that(&bookmarks).has_length(1);
+        assert_that(&tags_of(&bookmarks, "Wiki"))
+            .is_equal_to(vec!["platform".to_string(), "docs".to_string()]);
+    }
+
+    #[test]
+    fn merges_bookmark_files_with_default_tags_and_includes() {
+        let directory = tempfile::tempdir().unwrap();
+        write_file(
+            directory.path(),
+            "platform.yml",
+            "tags: [platform]\ninclude: [shared/*.yml]\nbookmarks:\n  - name: Grafana\n    url: https://grafana.example.com\n    tags: [metrics]\n",
+        );
+        std::fs::create_dir(directory.path().join("shared")).unwrap();
+        write_file(
+            directory.path().join("shared").as_path(),
+            "wiki.yml",
+            "- name: Wiki\n  url: https://wiki.example.com\n  tags: []\n",
+        );
+        write_file(
+            directory.path(),
+            "search.yml",
+            "- name: Google\n  url: https://google.com\n  tags: [search]\n",
+        );
+        let mut repository = FileSystemBookmarkRepositoryAdapter::new(
+            vec![
+                BookmarkSource::new(directory.path().join("platform.yml"), vec![]),
+                BookmarkSource::new(directory.path().join("search.yml"), vec!["web".to_string()]),
+            ],
+            Synonyms::default(),
+        );
+
+        repository.initialise().unwrap();
+        let bookmarks = repository.get_all().unwrap();
+
+        assert_that(&bookmarks).has_length(3);
+        assert_that(&tags_of(&bookmarks, "Grafana"))
+            .is_equal_to(vec!["platform".to_string(), "metrics".to_string()]);
+        assert_that(&tags_of(&bookmarks, "Wiki")).is_equal_to(vec!["platform".to_string()]);
+        assert_that(&tags_of(&bookmarks, "Google"))
+            .is_equal_to(vec!["web".to_string(), "search".to_string()]);
+        assert_that(&repository.files()).has_length(3);
+    }
+
+    #[test]
+    fn selects_the_file_format_by_extension_or_source_setting() {
+        let directory = tempfile::tempdir().unwrap();
+        write_file(
+            directory.path(),
+            "tools.json",
+            r#"[{"name": "GitHub", "url": "https://github.com", "tags": ["code"]}]"#,
+        );
+        write_file(
+            directory.path(),
+            "platform.toml",
+            "tags = [\"platform\"]\n\n[[bookmarks]]\nname = \"Grafana\"\nurl = \"https://grafana.example.com\"\n",
+        );
+        write_file(
+            directory.path(),
+            "generated.txt",
+            r#"{"bookmarks": [{"name": "Wiki", "url": "https://wiki.example.com"}]}"#,
+        );
+        let mut repository = FileSystemBookmarkRepositoryAdapter::new(
+            vec![
+                BookmarkSource::new(directory.path().join("tools.json"), vec![]),
+                BookmarkSource::new(directory.path().join("platform.toml"), vec![]),
+                BookmarkSource::new(directory.path().join("generated.txt"), vec![])
+                    .with_format(Some(BookmarkFileFormat::Json)),
+            ],
+            Synonyms::default(),
+        );
+
+        repository.initialise().unwrap();
+        let bookmarks = repository.get_all().unwrap();
+
+        assert_that(&bookmarks).has_length(3);
+        assert_that(&tags_of(&bookmarks, "GitHub")).is_equal_to(vec!["code".to_string()]);
+        assert_that(&tags_of(&bookmarks, "Grafana")).is_equal_to(vec!["platform".to_string()]);
+        assert_that(&tags_of(&bookmarks, "Wiki")).is_equal_to(Vec::<String>::new());
+    }
+
+    #[test]
+    fn reads_opt_in_formats_from_a_directory_only_when_its_source_names_them() {
+        let directory = tempfile::tempdir().unwrap();
+        write_file(
+            directory.path(),
+            "bookmarks.yml",
+            "- name: Wiki\n  url: https://wiki.example.com\n",
+        );
+        write_file(
+            directory.path(),
+            "exported.html",
+            r#"<DL><p><DT><A HREF="https://doc.rust-lang.org/">Rust</A></DL><p>"#,
+        );
+        write_file(
+            directory.path(),
+            "README.md",
+            "- [Grafana](https://grafana.example.com)\n",
+        );
+        let names = |source: BookmarkSource| {
+            let mut repository =
+                FileSystemBookmarkRepositoryAdapter::new(vec![source], Synonyms::default());
+            repository.initialise().unwrap();
+            repository
+                .get_all()
+                .unwrap()
+                .iter()
+                .map(|bookmark| bookmark.name().clone())
+                .collect::<Vec<String>>()
+        };
+
+        assert_that(&names(BookmarkSource::new(directory.path(), vec![])))
+            .is_equal_to(vec!["Wiki".to_string()]);
+        assert_that(&names(
+            BookmarkSource::new(directory.path(), vec![])
+                .with_format(Some(BookmarkFileFormat::Html)),
+        ))
+        .is_equal_to(vec!["Rust".to_string()]);
+        assert_that(&names(
+            BookmarkSource::new(directory.path(), vec![])
+                .with_format(Some(BookmarkFileFormat::Markdown)),
+        ))
+        .is_equal_to(vec!["Grafana".to_string()]);
+    }
+
+    #[test]
+    fn flattens_nested_groups_with_inherited_tags_and_group_path() {
+        let directory = tempfile::tempdir().unwrap();
+        write_file(
+            directory.path(),
+            "bookmarks.yml",
+            "- name: Google\n  url: https://google.com\n  tags: [search]\n- group: Platform\n  tags: [platform]\n  bookmarks:\n    - group: Monitoring\n      tags: [metrics]\n      bookmarks:\n        - name: Grafana\n          url: https://grafana.example.com\n          tags: [dashboards]\n",
+        );
+        let mut repository = FileSystemBookmarkRepositoryAdapter::new(
+            vec![BookmarkSource::new(
+                directory.path().join("bookmarks.yml"),
+                vec![],
+            )],
+            Synonyms::default(),
+        );
+
+        repository.initialise().unwrap();
+        let bookmarks = repository.get_all().unwrap();
+        let grafana = bookmarks
+            .iter()
+            .find(|bookmark| bookmark.name() == "Grafana")
+            .unwrap();
+
+        assert_that(&bookmarks).has_length(2);
+        assert_that(grafana.tags()).is_equal_to(vec![
+            "platform".to_string(),
+            "metrics".to_string(),
+            "dashboards".to_string(),
+        ]);
+        assert_that(grafana.group_path())
+            .is_equal_to(vec!["Platform".to_string(), "Monitoring".to_string()]);
+    }
+
+    #[test]
+    fn reports_duplicate_bookmarks_with_both_locations() {
+        let directory = tempfile::tempdir().unwrap();
+        write_file(
+            directory.path(),
+            "a.yml",
+            "- name: GitHub\n  url: https://github.com\n  tags: []\n",
+        );
+        write_file(
+            directory.path(),
+            "b.yml",
+            "- name: Google\n  url: https://google.com\n  tags: []\n- name: GitHub again\n  url: https://github.com\n  tags: []\n",
+        );
+        let mut repository = FileSystemBookmarkRepositoryAdapter::new(
+            vec![BookmarkSource::new(directory.path(), vec![])],
+            Synonyms::default(),
+        );
+
+        let error = repository.initialise().unwrap_err().to_string();
+
+        assert_that(&error).contains("https://github.com/");
+        assert_that(&error).contains("a.yml (entry 1)");
+        assert_that(&error).contains("b.yml (entry 2)");
+    }
+
+    #[test]
+    fn reads_remote_sources_from_their_cached_copy_without_fetching() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let url = url::Url::parse(&format!(
+            "http://{}/bookmarks.yml",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let directory = tempfile::tempdir().unwrap();
+        write_file(
+            directory.path(),
+            "cached.yml",
+            "- name: Wiki\n  url: https://wiki.example.com\n",
+        );
+        let mut repository = FileSystemBookmarkRepositoryAdapter::new(
+            vec![BookmarkSource::remote(
+                url,
+                Some(directory.path().join("cached.yml")),
+                None,
+                vec![],
+            )],
+            Synonyms::default(),
+        );
+
+        repository.initialise().unwrap();
+
+        assert_that(&repository.get_all().unwrap()).has_length(1);
+        assert_that(&listener.accept().is_err()).is_true();
+    }
+}
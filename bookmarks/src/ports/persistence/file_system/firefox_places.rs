@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{Connection, OpenFlags};
+use url::Url;
+
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+
+const TAGS_ROOT_GUID: &str = "tags________";
+const ROOT_GUIDS: [&str; 6] = [
+    "root________",
+    "menu________",
+    "toolbar_____",
+    "tags________",
+    "unfiled_____",
+    "mobile______",
+];
+const BOOKMARK_TYPE: i64 = 1;
+const FOLDER_TYPE: i64 = 2;
+
+pub(super) fn read_firefox_places(
+    places_file: &Path,
+) -> Result<Vec<PersistenceBookmark>, FirefoxPlacesError> {
+    let copy_directory = tempfile::tempdir()?;
+    let places_copy = copy_directory.path().join("places.sqlite");
+    std::fs::copy(places_file, &places_copy)?;
+    let wal_file = wal_file(places_file);
+    if wal_file.exists() {
+        std::fs::copy(wal_file, self::wal_file(&places_copy))?;
+    }
+
+    let connection = Connection::open_with_flags(
+        &places_copy,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    FirefoxPlaces::load(&connection)?.bookmarks(&connection)
+}
+
+/// SQLite keeps uncheckpointed changes in a file named after the database with a `-wal` suffix.
+fn wal_file(database_file: &Path) -> PathBuf {
+    let mut file_name = database_file
+        .file_name()
+        .map(OsString::from)
+        .unwrap_or_default();
+    file_name.push("-wal");
+    database_file.with_file_name(file_name)
+}
+
+struct Folder {
+    parent: i64,
+    title: String,
+    guid: String,
+}
+
+struct FirefoxPlaces {
+    folders: HashMap<i64, Folder>,
+    tags: HashMap<i64, Vec<String>>,
+    keywords: HashMap<i64, Vec<String>>,
+}
+
+impl FirefoxPlaces {
+    fn load(connection: &Connection) -> Result<Self, FirefoxPlacesError> {
+        let folders = connection
+            .prepare(
+                "SELECT id, parent, IFNULL(title, ''), guid FROM moz_bookmarks WHERE type = ?",
+            )?
+            .query_map([FOLDER_TYPE], |row| {
+                Ok((
+                    row.get(0)?,
+                    Folder {
+                        parent: row.get(1)?,
+                        title: row.get(2)?,
+                        guid: row.get(3)?,
+                    },
+                ))
+            })?
+            .collect::<Result<HashMap<i64, Folder>, rusqlite::Error>>()?;
+
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        connection
+            .prepare(
+                "SELECT b.fk, IFNULL(tag.title, '') FROM moz_bookmarks b \
+                 JOIN moz_bookmarks tag ON b.parent = tag.id \
+                 JOIN moz_bookmarks tags_root ON tag.parent = tags_root.id \
+                 WHERE b.type = ? AND tags_root.guid = ?",
+            )?
+            .query_map(rusqlite::params![BOOKMARK_TYPE, TAGS_ROOT_GUID], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<(i64, String)>, rusqlite::Error>>()?
+            .into_iter()
+            .for_each(|(place_id, tag)| tags.entry(place_id).or_default().push(tag));
+
+        let mut keywords: HashMap<i64, Vec<String>> = HashMap::new();
+        connection
+            .prepare("SELECT place_id, keyword FROM moz_keywords ORDER BY keyword")?
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<(i64, String)>, rusqlite::Error>>()?
+            .into_iter()
+            .for_each(|(place_id, keyword)| keywords.entry(place_id).or_default().push(keyword));
+
+        Ok(FirefoxPlaces {
+            folders,
+            tags,
+            keywords,
+        })
+    }
+
+    fn bookmarks(
+        &self,
+        connection: &Connection,
+    ) -> Result<Vec<PersistenceBookmark>, FirefoxPlacesError> {
+        let rows = connection
+            .prepare(
+                "SELECT b.fk, b.parent, IFNULL(b.title, ''), b.dateAdded, p.url FROM moz_bookmarks b \
+                 JOIN moz_places p ON p.id = b.fk \
+                 WHERE b.type = ? ORDER BY b.parent, b.position",
+            )?
+            .query_map([BOOKMARK_TYPE], |row| {
+                Ok(BookmarkRow {
+                    place_id: row.get(0)?,
+                    parent: row.get(1)?,
+                    title: row.get(2)?,
+                    date_added: row.get(3)?,
+                    url: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<BookmarkRow>, rusqlite::Error>>()?;
+
+        Ok(rows
+            .into_iter()
+            .filter(|row| !self.is_tag_entry(row.parent))
+            .filter_map(|row| self.bookmark(row))
+            .collect())
+    }
+
+    fn bookmark(&self, row: BookmarkRow) -> Option<PersistenceBookmark> {
+        let url = match Url::parse(&row.url) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => url,
+            _ => {
+                log::debug!("Skipping bookmark with unsupported url: {}", row.url);
+                return None;
+            }
+        };
+        let mut tags = self.folder_path(row.parent);
+        self.tags
+            .get(&row.place_id)
+            .into_iter()
+            .flatten()
+            .for_each(|tag| {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            });
+        let name = if row.title.is_empty() {
+            url.to_string()
+        } else {
+            row.title
+        };
+
+        Some(PersistenceBookmark::new(
+            url,
+            name,
+            None,
+            tags,
+            self.keywords
+                .get(&row.place_id)
+                .cloned()
+                .unwrap_or_default(),
+            row.date_added.and_then(firefox_timestamp),
+        ))
+    }
+
+    fn folder_path(&self, mut folder_id: i64) -> Vec<String> {
+        let mut path = vec![];
+        while let Some(folder) = self.folders.get(&folder_id) {
+            if ROOT_GUIDS.contains(&folder.guid.as_str()) {
+                break;
+            }
+            path.push(folder.title.clone());
+            folder_id = folder.parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn is_tag_entry(&self, parent: i64) -> bool {
+        self.folders
+            .get(&parent)
+            .and_then(|tag| self.folders.get(&tag.parent))
+            .is_some_and(|tags_root| tags_root.guid == TAGS_ROOT_GUID)
+    }
+}
+
+struct BookmarkRow {
+    place_id: i64,
+    parent: i64,
+    title: String,
+    date_added: Option<i64>,
+    url: String,
+}
+
+fn firefox_timestamp(microseconds: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(microseconds / 1_000_000, 0).single()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum FirefoxPlacesError {
+    #[error("Could not copy places database: {0}")]
+    Copy(#[from] std::io::Error),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    const PLACES: &str = "
+        CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url LONGVARCHAR, title LONGVARCHAR);
+        CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, parent INTEGER,
+            position INTEGER, title LONGVARCHAR, dateAdded INTEGER, guid TEXT);
+        CREATE TABLE moz_keywords (id INTEGER PRIMARY KEY, keyword TEXT, place_id INTEGER);
+        INSERT INTO moz_places VALUES (1, 'https://grafana.example.com/', 'Grafana');
+        INSERT INTO moz_places VALUES (2, 'place:sort=8', NULL);
+        INSERT INTO moz_bookmarks VALUES (1, 2, NULL, 0, 0, '', NULL, 'root________');
+        INSERT INTO moz_bookmarks VALUES (2, 2, NULL, 1, 0, 'toolbar', NULL, 'toolbar_____');
+        INSERT INTO moz_bookmarks VALUES (3, 2, NULL, 1, 1, 'tags', NULL, 'tags________');
+        INSERT INTO moz_bookmarks VALUES (4, 2, NULL, 2, 0, 'Platform', NULL, 'folder000001');
+        INSERT INTO moz_bookmarks VALUES (5, 1, 1, 4, 0, 'Grafana', 1600000000000000, 'bookmark0001');
+        INSERT INTO moz_bookmarks VALUES (6, 2, NULL, 3, 0, 'metrics', NULL, 'tagfolder001');
+        INSERT INTO moz_bookmarks VALUES (7, 1, 1, 6, 0, NULL, NULL, 'tagentry0001');
+        INSERT INTO moz_bookmarks VALUES (8, 1, 2, 2, 1, 'Recent', NULL, 'bookmark0002');
+        INSERT INTO moz_keywords VALUES (1, 'gf', 1);
+    ";
+
+    #[test]
+    fn reads_folders_tags_and_keywords() {
+        let directory = tempfile::tempdir().unwrap();
+        let places_file = directory.path().join("places.sqlite");
+        Connection::open(&places_file)
+            .unwrap()
+            .execute_batch(PLACES)
+            .unwrap();
+
+        let bookmarks = read_firefox_places(&places_file).unwrap();
+
+        assert_that(&bookmarks).has_length(1);
+        let grafana = &bookmarks[0];
+        assert_that(grafana.name()).is_equal_to("Grafana".to_string());
+        assert_that(grafana.tags())
+            .is_equal_to(vec!["Platform".to_string(), "metrics".to_string()]);
+        assert_that(grafana.aliases()).is_equal_to(vec!["gf".to_string()]);
+        assert_that(&grafana.created_at()).is_equal_to(Some(Utc.timestamp(1_600_000_000, 0)));
+    }
+
+    #[test]
+    fn reads_changes_from_the_write_ahead_log_of_a_renamed_database() {
+        let directory = tempfile::tempdir().unwrap();
+        let places_file = directory.path().join("places-backup.sqlite");
+        let connection = Connection::open(&places_file).unwrap();
+        connection
+            .execute_batch("PRAGMA journal_mode = WAL; PRAGMA wal_autocheckpoint = 0;")
+            .unwrap();
+        connection.execute_batch(PLACES).unwrap();
+
+        let bookmarks = read_firefox_places(&places_file).unwrap();
+
+        assert_that(&directory.path().join("places-backup.sqlite-wal").exists()).is_true();
+        assert_that(&bookmarks).has_length(1);
+    }
+}
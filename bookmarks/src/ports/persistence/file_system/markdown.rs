@@ -0,0 +1,355 @@
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use url::Url;
+
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+use crate::ports::persistence::file_system::persistence_entry::{
+    PersistenceEntry, PersistenceGroup,
+};
+
+/// Reads the bookmarks of a Markdown file. A note with invalid front matter is skipped, so one
+/// broken note does not keep the rest of a vault from loading.
+pub(super) fn parse_markdown(file: &Path, content: &str) -> Vec<PersistenceEntry> {
+    if let Some((front_matter, body)) = split_front_matter(content) {
+        let front_matter: FrontMatter = match serde_yaml::from_str(front_matter) {
+            Ok(front_matter) => front_matter,
+            Err(err) => {
+                log::warn!(
+                    "Skipping {} with invalid front matter: {}",
+                    file.display(),
+                    err
+                );
+                return vec![];
+            }
+        };
+        if let Some(url) = front_matter.url.clone() {
+            return note_bookmark(file, &url, front_matter, body)
+                .map(PersistenceEntry::Bookmark)
+                .into_iter()
+                .collect();
+        }
+        return parse_link_list(body);
+    }
+    parse_link_list(content)
+}
+
+fn split_front_matter(content: &str) -> Option<(&str, &str)> {
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))?;
+    let end = rest
+        .match_indices("\n---")
+        .map(|(index, _)| index)
+        .find(|index| {
+            let after = &rest[index + 4..];
+            after.is_empty() || after.starts_with('\n') || after.starts_with("\r\n")
+        })?;
+    Some((
+        &rest[..end],
+        rest[end + 4..].trim_start_matches(['\r', '\n']),
+    ))
+}
+
+fn note_bookmark(
+    file: &Path,
+    url: &str,
+    front_matter: FrontMatter,
+    body: &str,
+) -> Option<PersistenceBookmark> {
+    let url = web_url(url)?;
+    let title = front_matter
+        .title
+        .or_else(|| {
+            body.lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|heading| heading.trim().to_string())
+        })
+        .or_else(|| {
+            file.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| url.to_string());
+    let description = Some(body.trim().to_string()).filter(|body| !body.is_empty());
+    let created_at = front_matter
+        .created_at
+        .or(front_matter.created)
+        .as_ref()
+        .and_then(date_value);
+
+    Some(PersistenceBookmark::new(
+        url,
+        title,
+        description,
+        front_matter.tags.into_vec(),
+        front_matter.aliases.into_vec(),
+        created_at,
+    ))
+}
+
+fn parse_link_list(content: &str) -> Vec<PersistenceEntry> {
+    let mut sections: Vec<Section> = vec![Section::default()];
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if let Some((level, heading)) = heading(trimmed) {
+            while sections.len() > 1 && sections.last().unwrap().level >= level {
+                close_section(&mut sections);
+            }
+            sections.push(Section {
+                level,
+                name: Some(heading),
+                entries: vec![],
+            });
+        } else if let Some(bookmark) = list_item_bookmark(trimmed) {
+            sections
+                .last_mut()
+                .unwrap()
+                .entries
+                .push(PersistenceEntry::Bookmark(bookmark));
+        }
+    }
+
+    while sections.len() > 1 {
+        close_section(&mut sections);
+    }
+    sections.pop().unwrap().entries
+}
+
+#[derive(Default)]
+struct Section {
+    level: usize,
+    name: Option<String>,
+    entries: Vec<PersistenceEntry>,
+}
+
+fn close_section(sections: &mut Vec<Section>) {
+    let section = sections.pop().unwrap();
+    let parent = sections.last_mut().unwrap();
+    match section.name {
+        Some(name) if !section.entries.is_empty() => {
+            parent
+                .entries
+                .push(PersistenceEntry::Group(PersistenceGroup::new(
+                    name.clone(),
+                    vec![name],
+                    section.entries,
+                )))
+        }
+        _ => parent.entries.extend(section.entries),
+    }
+}
+
+fn heading(line: &str) -> Option<(usize, String)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let heading = line[level..]
+        .strip_prefix(' ')?
+        .trim()
+        .trim_end_matches('#');
+    Some((level, heading.trim().to_string())).filter(|(_, heading)| !heading.is_empty())
+}
+
+fn list_item_bookmark(line: &str) -> Option<PersistenceBookmark> {
+    let item = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .or_else(|| {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            line[digits..].strip_prefix(". ").filter(|_| digits > 0)
+        })?
+        .trim_start();
+    let item = item
+        .strip_prefix("[ ] ")
+        .or_else(|| item.strip_prefix("[x] "))
+        .unwrap_or(item);
+
+    let name_end = item.strip_prefix('[')?.find("](")? + 1;
+    let name = &item[1..name_end];
+    let rest = &item[name_end + 2..];
+    let url_end = closing_parenthesis(rest)?;
+    let url = web_url(rest[..url_end].split_whitespace().next()?)?;
+    let description = rest[url_end + 1..]
+        .trim()
+        .trim_start_matches(['-', ':', '—', '–'])
+        .trim();
+    let name = if name.trim().is_empty() {
+        url.to_string()
+    } else {
+        name.trim().to_string()
+    };
+
+    Some(PersistenceBookmark::new(
+        url,
+        name,
+        Some(description.to_string()).filter(|description| !description.is_empty()),
+        vec![],
+        vec![],
+        None,
+    ))
+}
+
+/// Finds the parenthesis closing a link target, skipping balanced pairs within the URL.
+fn closing_parenthesis(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, character) in text.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(index),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn web_url(url: &str) -> Option<Url> {
+    match Url::parse(url) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Some(url),
+        _ => {
+            log::debug!("Skipping link with unsupported url: {}", url);
+            None
+        }
+    }
+}
+
+fn date_value(value: &serde_yaml::Value) -> Option<DateTime<Utc>> {
+    let date = value.as_str()?;
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| Utc.from_utc_datetime(&date))
+        })
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct FrontMatter {
+    url: Option<String>,
+    title: Option<String>,
+    tags: StringList,
+    aliases: StringList,
+    created_at: Option<serde_yaml::Value>,
+    created: Option<serde_yaml::Value>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum StringList {
+    Empty(()),
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Default for StringList {
+    fn default() -> Self {
+        StringList::Many(vec![])
+    }
+}
+
+impl StringList {
+    fn into_vec(self) -> Vec<String> {
+        let values = match self {
+            StringList::Empty(()) => vec![],
+            StringList::One(values) => values
+                .split([',', ' '])
+                .map(str::to_string)
+                .collect::<Vec<String>>(),
+            StringList::Many(values) => values,
+        };
+        values
+            .iter()
+            .map(|value| value.trim().trim_start_matches('#').to_string())
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn groups_link_list_items_by_heading() {
+        let readme = "# Platform team\n\nIntro with an [inline](https://inline.example.com) link.\n\n\
+                      ## Monitoring\n\n- [Grafana](https://grafana.example.com) - service dashboards\n\
+                      - [Runbooks](../runbooks.md)\n- [Rust](https://en.wikipedia.org/wiki/Rust_(programming_language)) (language)\n\n```\n- [Ignored](https://ignored.example.com)\n```\n\n\
+                      ## Docs\n\n1. [Wiki](https://wiki.example.com)\n";
+
+        let entries = parse_markdown(Path::new("README.md"), readme);
+
+        assert_that(&serde_yaml::to_string(&entries).unwrap()).is_equal_to(
+            r#"---
+- group: Platform team
+  tags:
+    - Platform team
+  bookmarks:
+    - group: Monitoring
+      tags:
+        - Monitoring
+      bookmarks:
+        - url: "https://grafana.example.com/"
+          name: Grafana
+          description: service dashboards
+        - url: "https://en.wikipedia.org/wiki/Rust_(programming_language)"
+          name: Rust
+          description: (language)
+    - group: Docs
+      tags:
+        - Docs
+      bookmarks:
+        - url: "https://wiki.example.com/"
+          name: Wiki
+"#
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn reads_a_note_with_front_matter_as_one_bookmark() {
+        let note = "---\nurl: https://grafana.example.com\ntags: [metrics, \"#ops\"]\naliases: gf\ntitle:\ncreated: 2021-03-04\n---\n\nDashboards for every service.\n";
+
+        let entries = parse_markdown(Path::new("vault/Grafana.md"), note);
+
+        assert_that(&serde_yaml::to_string(&entries).unwrap()).is_equal_to(
+            r#"---
+- url: "https://grafana.example.com/"
+  name: Grafana
+  description: Dashboards for every service.
+  tags:
+    - metrics
+    - ops
+  aliases:
+    - gf
+  created_at: "2021-03-04T00:00:00Z"
+"#
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn skips_a_note_with_invalid_front_matter() {
+        let note = "---\nurl: https://grafana.example.com\ntags: [metrics\n---\n\n- [Wiki](https://wiki.example.com)\n";
+
+        let entries = parse_markdown(Path::new("vault/Grafana.md"), note);
+
+        assert_that(&entries).is_empty();
+    }
+}
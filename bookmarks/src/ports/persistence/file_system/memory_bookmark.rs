@@ -1,15 +1,20 @@
+use chrono::{DateTime, Utc};
 use url::Url;
 use uuid::Uuid;
 
 use crate::domain::bookmark::Bookmark;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug)]
 pub(super) struct MemoryBookmark {
     id: Uuid,
     url: Url,
     name: String,
     description: Option<String>,
     tags: Vec<String>,
+    aliases: Vec<String>,
+    group_path: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
 }
 
 impl MemoryBookmark {
@@ -26,9 +31,29 @@ impl MemoryBookmark {
             name,
             description,
             tags,
+            aliases: vec![],
+            group_path: vec![],
+            created_at: None,
+            updated_at: None,
         }
     }
 
+    pub(crate) fn with_aliases(self, aliases: Vec<String>) -> Self {
+        MemoryBookmark { aliases, ..self }
+    }
+
+    pub(crate) fn with_group_path(self, group_path: Vec<String>) -> Self {
+        MemoryBookmark { group_path, ..self }
+    }
+
+    pub(crate) fn with_created_at(self, created_at: Option<DateTime<Utc>>) -> Self {
+        MemoryBookmark { created_at, ..self }
+    }
+
+    pub(crate) fn with_updated_at(self, updated_at: Option<DateTime<Utc>>) -> Self {
+        MemoryBookmark { updated_at, ..self }
+    }
+
     pub(crate) fn id(&self) -> Uuid {
         self.id
     }
@@ -48,6 +73,22 @@ impl MemoryBookmark {
     pub(crate) fn tags(&self) -> &Vec<String> {
         &self.tags
     }
+
+    pub(crate) fn aliases(&self) -> &Vec<String> {
+        &self.aliases
+    }
+
+    pub(crate) fn group_path(&self) -> &Vec<String> {
+        &self.group_path
+    }
+
+    pub(crate) fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub(crate) fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
 }
 
 impl From<&MemoryBookmark> for Bookmark {
@@ -59,5 +100,9 @@ impl From<&MemoryBookmark> for Bookmark {
             bookmark.description().cloned(),
             bookmark.tags().clone(),
         )
+        .with_aliases(bookmark.aliases().clone())
+        .with_group_path(bookmark.group_path().clone())
+        .with_created_at(bookmark.created_at())
+        .with_updated_at(bookmark.updated_at())
     }
 }
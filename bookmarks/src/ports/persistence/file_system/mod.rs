@@ -1,5 +1,27 @@
+pub(crate) use bookmark_conversion::*;
+pub(crate) use bookmark_edit::write_bookmarks_to_file;
+pub(crate) use bookmark_file_format::*;
+pub(crate) use bookmark_import::*;
+pub(crate) use bookmark_source::*;
+pub(crate) use csv_bookmarks::CsvColumns;
 pub(crate) use file_system_bookmark_repository_adapter::*;
+pub(crate) use remote_bookmark_file::*;
 
+mod bookmark_conversion;
+mod bookmark_edit;
+mod bookmark_file_format;
+mod bookmark_files;
+mod bookmark_import;
+mod bookmark_source;
+mod bookmarking_services;
+mod chromium_bookmarks;
+mod csv_bookmarks;
 mod file_system_bookmark_repository_adapter;
+mod firefox_places;
+mod markdown;
 mod memory_bookmark;
+mod netscape_html;
 mod persistence_bookmark;
+mod persistence_entry;
+mod remote_bookmark_file;
+mod xbel;
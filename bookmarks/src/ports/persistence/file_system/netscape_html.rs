@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use url::Url;
+
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+use crate::ports::persistence::file_system::persistence_entry::{
+    PersistenceEntry, PersistenceGroup,
+};
+
+const SUPPORTED_SCHEMES: [&str; 3] = ["http", "https", "ftp"];
+
+pub(super) fn parse_netscape_html(content: &str) -> Vec<PersistenceEntry> {
+    let mut folders: Vec<Folder> = vec![Folder::default()];
+    let mut pending_folder: Option<String> = None;
+    let mut last_entry = LastEntry::None;
+    let mut tokens = tokenize(content).into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            HtmlToken::Start { name, attributes } => match name.as_str() {
+                "h3" => {
+                    pending_folder = Some(text_until(&mut tokens, "h3"));
+                    last_entry = LastEntry::Folder;
+                }
+                "dl" => folders.push(Folder {
+                    name: pending_folder.take(),
+                    entries: vec![],
+                }),
+                "a" => {
+                    let name = text_until(&mut tokens, "a");
+                    last_entry = match bookmark(name, &attributes) {
+                        Some(bookmark) => {
+                            folders
+                                .last_mut()
+                                .unwrap()
+                                .entries
+                                .push(PendingEntry::Bookmark(bookmark));
+                            LastEntry::Bookmark
+                        }
+                        None => LastEntry::None,
+                    };
+                }
+                "dd" => {
+                    let description = match tokens.peek() {
+                        Some(HtmlToken::Text(text)) => text.trim().to_string(),
+                        _ => String::new(),
+                    };
+                    if !description.is_empty() && last_entry == LastEntry::Bookmark {
+                        if let Some(PendingEntry::Bookmark(bookmark)) =
+                            folders.last_mut().unwrap().entries.last_mut()
+                        {
+                            bookmark.description = Some(description);
+                        }
+                    }
+                    last_entry = LastEntry::None;
+                }
+                _ => {}
+            },
+            HtmlToken::End(name) if name == "dl" && folders.len() > 1 => {
+                let folder = folders.pop().unwrap();
+                folders
+                    .last_mut()
+                    .unwrap()
+                    .entries
+                    .push(PendingEntry::Folder(folder));
+                last_entry = LastEntry::None;
+            }
+            _ => {}
+        }
+    }
+
+    while folders.len() > 1 {
+        let folder = folders.pop().unwrap();
+        folders
+            .last_mut()
+            .unwrap()
+            .entries
+            .push(PendingEntry::Folder(folder));
+    }
+    into_entries(folders.pop().unwrap().entries)
+}
+
+#[derive(Default)]
+struct Folder {
+    name: Option<String>,
+    entries: Vec<PendingEntry>,
+}
+
+enum PendingEntry {
+    Bookmark(PendingBookmark),
+    Folder(Folder),
+}
+
+struct PendingBookmark {
+    url: Url,
+    name: String,
+    description: Option<String>,
+    tags: Vec<String>,
+    aliases: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(PartialEq)]
+enum LastEntry {
+    None,
+    Bookmark,
+    Folder,
+}
+
+fn into_entries(entries: Vec<PendingEntry>) -> Vec<PersistenceEntry> {
+    entries
+        .into_iter()
+        .flat_map(|entry| match entry {
+            PendingEntry::Bookmark(bookmark) => {
+                vec![PersistenceEntry::Bookmark(
+                    PersistenceBookmark::new(
+                        bookmark.url,
+                        bookmark.name,
+                        bookmark.description,
+                        bookmark.tags,
+                        bookmark.aliases,
+                        bookmark.created_at,
+                    )
+                    .with_updated_at(bookmark.updated_at),
+                )]
+            }
+            PendingEntry::Folder(Folder {
+                name: Some(name),
+                entries,
+            }) => vec![PersistenceEntry::Group(PersistenceGroup::new(
+                name.clone(),
+                vec![name],
+                into_entries(entries),
+            ))],
+            PendingEntry::Folder(folder) => into_entries(folder.entries),
+        })
+        .collect()
+}
+
+fn bookmark(name: String, attributes: &HashMap<String, String>) -> Option<PendingBookmark> {
+    let href = attributes.get("href")?;
+    let url = match Url::parse(href) {
+        Ok(url) if SUPPORTED_SCHEMES.contains(&url.scheme()) => url,
+        _ => {
+            log::debug!("Skipping bookmark with unsupported url: {}", href);
+            return None;
+        }
+    };
+    let tags = attributes
+        .get("tags")
+        .map(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let aliases = attributes
+        .get("shortcuturl")
+        .map(|alias| alias.trim())
+        .filter(|alias| !alias.is_empty())
+        .map(|alias| vec![alias.to_string()])
+        .unwrap_or_default();
+    let name = if name.is_empty() {
+        url.to_string()
+    } else {
+        name
+    };
+
+    Some(PendingBookmark {
+        url,
+        name,
+        description: None,
+        tags,
+        aliases,
+        created_at: timestamp(attributes, "add_date"),
+        updated_at: timestamp(attributes, "last_modified"),
+    })
+}
+
+fn timestamp(attributes: &HashMap<String, String>, name: &str) -> Option<DateTime<Utc>> {
+    attributes
+        .get(name)
+        .and_then(|seconds| seconds.trim().parse::<i64>().ok())
+        .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+}
+
+fn text_until<I: Iterator<Item = HtmlToken>>(
+    tokens: &mut std::iter::Peekable<I>,
+    end_tag: &str,
+) -> String {
+    let mut text = String::new();
+    while let Some(token) = tokens.peek() {
+        match token {
+            HtmlToken::Text(content) => text.push_str(content),
+            HtmlToken::End(name) if name == end_tag => {
+                tokens.next();
+                break;
+            }
+            HtmlToken::Start { name, .. } if name == "dt" || name == "dl" || name == "dd" => break,
+            HtmlToken::End(name) if name == "dl" => break,
+            _ => {}
+        }
+        tokens.next();
+    }
+    text.trim().to_string()
+}
+
+enum HtmlToken {
+    Start {
+        name: String,
+        attributes: HashMap<String, String>,
+    },
+    End(String),
+    Text(String),
+}
+
+fn tokenize(content: &str) -> Vec<HtmlToken> {
+    let mut tokens = vec![];
+    let mut rest = content;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with('<') {
+            let end = match rest.find('>') {
+                Some(end) => end,
+                None => break,
+            };
+            if let Some(token) = tag_token(&rest[1..end]) {
+                tokens.push(token);
+            }
+            rest = &rest[end + 1..];
+            continue;
+        }
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = decode_entities(&rest[..end]);
+        if !text.trim().is_empty() {
+            tokens.push(HtmlToken::Text(text));
+        }
+        rest = &rest[end..];
+    }
+
+    tokens
+}
+
+fn tag_token(tag: &str) -> Option<HtmlToken> {
+    if let Some(name) = tag.strip_prefix('/') {
+        return Some(HtmlToken::End(name.trim().to_lowercase()));
+    }
+    if tag.starts_with('!') || tag.starts_with('?') {
+        return None;
+    }
+
+    let tag = tag.trim_end_matches('/');
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    Some(HtmlToken::Start {
+        name: tag[..name_end].to_lowercase(),
+        attributes: attributes(&tag[name_end..]),
+    })
+}
+
+fn attributes(mut rest: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return attributes;
+        }
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let value = value.trim_start();
+                let (content, remaining) = match value.chars().next() {
+                    Some(quote @ '"') | Some(quote @ '\'') => {
+                        let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+                        (&value[1..end], value.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                        (&value[..end], &value[end..])
+                    }
+                };
+                rest = remaining;
+                decode_entities(content)
+            }
+            None => String::new(),
+        };
+        attributes.insert(name, value);
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = match entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            std::char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    const EXPORT: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file. -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1600000000" PERSONAL_TOOLBAR_FOLDER="true">Work</H3>
+    <DL><p>
+        <DT><H3>Platform &amp; Ops</H3>
+        <DL><p>
+            <DT><A HREF="https://grafana.example.com/?a=1&amp;b=2" ADD_DATE="1600000100" LAST_MODIFIED="1700000000" TAGS="metrics,dashboards">Grafana</A>
+            <DD>Service dashboards
+        </DL><p>
+        <DT><A HREF="place:sort=8">Recent tags</A>
+        <DT><A HREF="file:///home/ada/notes.html">Notes</A>
+    </DL><p>
+    <DT><A HREF="https://github.com">GitHub</A>
+</DL><p>
+"#;
+
+    #[test]
+    fn maps_folders_to_tagged_groups_and_reads_bookmark_attributes() {
+        let entries = parse_netscape_html(EXPORT);
+
+        assert_that(&serde_yaml::to_string(&entries).unwrap()).is_equal_to(
+            r#"---
+- group: Work
+  tags:
+    - Work
+  bookmarks:
+    - group: Platform & Ops
+      tags:
+        - Platform & Ops
+      bookmarks:
+        - url: "https://grafana.example.com/?a=1&b=2"
+          name: Grafana
+          description: Service dashboards
+          tags:
+            - metrics
+            - dashboards
+          created_at: "2020-09-13T12:28:20Z"
+          updated_at: "2023-11-14T22:13:20Z"
+- url: "https://github.com/"
+  name: GitHub
+"#
+            .to_string(),
+        );
+    }
+}
@@ -1,14 +1,56 @@
+use chrono::{DateTime, Utc};
 use url::Url;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub(super) struct PersistenceBookmark {
     url: Url,
     name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
 }
 
 impl PersistenceBookmark {
+    pub(crate) fn new(
+        url: Url,
+        name: String,
+        description: Option<String>,
+        tags: Vec<String>,
+        aliases: Vec<String>,
+        created_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        PersistenceBookmark {
+            url,
+            name,
+            description,
+            tags,
+            aliases,
+            created_at,
+            updated_at: None,
+        }
+    }
+
+    pub(crate) fn with_updated_at(self, updated_at: Option<DateTime<Utc>>) -> Self {
+        PersistenceBookmark { updated_at, ..self }
+    }
+
+    pub(crate) fn with_inherited_tags(self, inherited_tags: &[String]) -> Self {
+        let mut tags = inherited_tags.to_vec();
+        self.tags.into_iter().for_each(|tag| {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        });
+        PersistenceBookmark { tags, ..self }
+    }
+
     pub(crate) fn url(&self) -> &Url {
         &self.url
     }
@@ -24,4 +66,16 @@ impl PersistenceBookmark {
     pub(crate) fn tags(&self) -> &Vec<String> {
         &self.tags
     }
+
+    pub(crate) fn aliases(&self) -> &Vec<String> {
+        &self.aliases
+    }
+
+    pub(crate) fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    pub(crate) fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
 }
@@ -0,0 +1,98 @@
+use std::convert::TryFrom;
+
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(try_from = "serde_yaml::Value")]
+pub(super) enum PersistenceEntry {
+    Bookmark(PersistenceBookmark),
+    Group(PersistenceGroup),
+}
+
+impl TryFrom<serde_yaml::Value> for PersistenceEntry {
+    type Error = String;
+
+    fn try_from(value: serde_yaml::Value) -> Result<Self, Self::Error> {
+        let is_group = value
+            .as_mapping()
+            .is_some_and(|mapping| mapping.contains_key(&serde_yaml::Value::from("group")));
+
+        if is_group {
+            serde_yaml::from_value(value)
+                .map(PersistenceEntry::Group)
+                .map_err(|err| format!("invalid group: {}", err))
+        } else {
+            serde_yaml::from_value(value)
+                .map(PersistenceEntry::Bookmark)
+                .map_err(|err| format!("invalid bookmark: {}", err))
+        }
+    }
+}
+
+impl serde::Serialize for PersistenceEntry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PersistenceEntry::Bookmark(bookmark) => bookmark.serialize(serializer),
+            PersistenceEntry::Group(group) => group.serialize(serializer),
+        }
+    }
+}
+
+pub(super) fn flatten_entries(
+    entries: Vec<PersistenceEntry>,
+    inherited_tags: &[String],
+) -> Vec<PersistenceBookmark> {
+    entries
+        .into_iter()
+        .flat_map(|entry| match entry {
+            PersistenceEntry::Bookmark(bookmark) => {
+                vec![bookmark.with_inherited_tags(inherited_tags)]
+            }
+            PersistenceEntry::Group(group) => {
+                let mut group_tags = inherited_tags.to_vec();
+                group_tags.extend(group.tags().iter().cloned());
+                flatten_entries(group.into_entries(), &group_tags)
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct PersistenceGroup {
+    group: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default)]
+    bookmarks: Vec<PersistenceEntry>,
+}
+
+impl PersistenceGroup {
+    pub(super) fn new(group: String, tags: Vec<String>, bookmarks: Vec<PersistenceEntry>) -> Self {
+        PersistenceGroup {
+            group,
+            tags,
+            bookmarks,
+        }
+    }
+
+    pub(super) fn name(&self) -> &String {
+        &self.group
+    }
+
+    pub(super) fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub(super) fn entries(&self) -> &Vec<PersistenceEntry> {
+        &self.bookmarks
+    }
+
+    pub(super) fn entries_mut(&mut self) -> &mut Vec<PersistenceEntry> {
+        &mut self.bookmarks
+    }
+
+    pub(super) fn into_entries(self) -> Vec<PersistenceEntry> {
+        self.bookmarks
+    }
+}
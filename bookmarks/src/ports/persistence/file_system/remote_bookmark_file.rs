@@ -0,0 +1,276 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use url::Url;
+
+use crate::ports::persistence::file_system::bookmark_file_format::BookmarkFileFormat;
+use crate::ports::persistence::file_system::bookmark_files::parse_bookmark_file;
+use crate::ports::persistence::file_system::bookmark_source::BookmarkSource;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub(crate) struct RemoteBookmarkFile {
+    url: Url,
+    poll_interval: Option<Duration>,
+}
+
+impl RemoteBookmarkFile {
+    pub(crate) fn new(url: Url, poll_interval: Option<Duration>) -> Self {
+        RemoteBookmarkFile { url, poll_interval }
+    }
+
+    pub(crate) fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub(crate) fn poll_interval(&self) -> Option<Duration> {
+        self.poll_interval
+    }
+}
+
+/// Refreshes the local copies of all remote sources. A copy that cannot be refreshed is kept.
+pub(crate) fn refresh_remote_sources(sources: &[BookmarkSource]) {
+    for source in sources {
+        if let Err(err) = refresh_remote_source(source) {
+            log::warn!("{}, keeping the cached copy", err);
+        }
+    }
+}
+
+/// Fetches a remote source into its local copy, returning whether the copy changed. The request
+/// is conditional on the `ETag`/`Last-Modified` of the copy, and a response that cannot be parsed
+/// leaves the copy untouched.
+pub(crate) fn refresh_remote_source(
+    source: &BookmarkSource,
+) -> Result<bool, RemoteBookmarkFileError> {
+    let remote = match source.remote_file() {
+        Some(remote) => remote,
+        None => return Ok(false),
+    };
+    let cache_file = source.path();
+    let format = source
+        .format()
+        .unwrap_or_else(|| BookmarkFileFormat::detect(cache_file));
+    let validators = if cache_file.is_file() {
+        CacheValidators::read(cache_file)
+    } else {
+        CacheValidators::default()
+    };
+
+    let mut request = ureq::get(remote.url.as_str()).timeout(FETCH_TIMEOUT);
+    if let Some(etag) = &validators.etag {
+        request = request.set("If-None-Match", etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.set("If-Modified-Since", last_modified);
+    }
+    let response = request.call().map_err(|err| match err {
+        ureq::Error::Status(status, _) => {
+            RemoteBookmarkFileError::Status(remote.url.clone(), status)
+        }
+        ureq::Error::Transport(err) => {
+            RemoteBookmarkFileError::Transport(remote.url.clone(), transport_error_message(&err))
+        }
+    })?;
+    if response.status() == 304 {
+        log::debug!("{} is unchanged", remote.url);
+        return Ok(false);
+    }
+
+    let validators = CacheValidators {
+        etag: response.header("ETag").map(str::to_string),
+        last_modified: response.header("Last-Modified").map(str::to_string),
+    };
+    let content = read_to_limit(response.into_reader(), MAX_FILE_BYTES)
+        .map_err(|err| RemoteBookmarkFileError::Transport(remote.url.clone(), err.to_string()))?
+        .ok_or_else(|| RemoteBookmarkFileError::TooLarge(remote.url.clone(), MAX_FILE_BYTES))?;
+    let document = parse_bookmark_file(cache_file, format, &content)
+        .map_err(|err| RemoteBookmarkFileError::Invalid(remote.url.clone(), err.to_string()))?;
+    if !document.include().is_empty() {
+        return Err(RemoteBookmarkFileError::Invalid(
+            remote.url.clone(),
+            "remote bookmark files cannot include other files".to_string(),
+        ));
+    }
+
+    let changed = std::fs::read(cache_file).map_or(true, |cached| cached != content);
+    if changed {
+        write_cache_file(cache_file, &content)?;
+        log::info!("Fetched {} into {}", remote.url, cache_file.display());
+    }
+    validators.write(cache_file);
+    Ok(changed)
+}
+
+/// Reads at most `limit` bytes, returning `None` when there is more.
+fn read_to_limit(reader: impl Read, limit: u64) -> std::io::Result<Option<Vec<u8>>> {
+    let mut content = vec![];
+    reader.take(limit + 1).read_to_end(&mut content)?;
+    Ok(Some(content).filter(|content| content.len() as u64 <= limit))
+}
+
+/// Describes a transport error without the url ureq prefixes it with.
+fn transport_error_message(err: &ureq::Transport) -> String {
+    let message = err
+        .message()
+        .map_or_else(|| err.kind().to_string(), str::to_string);
+    match std::error::Error::source(err) {
+        Some(source) => format!("{}: {}", message, source),
+        None => message,
+    }
+}
+
+fn write_cache_file(cache_file: &Path, content: &[u8]) -> Result<(), RemoteBookmarkFileError> {
+    let cache_error = |err| RemoteBookmarkFileError::Cache(cache_file.to_path_buf(), err);
+    if let Some(directory) = cache_file.parent() {
+        std::fs::create_dir_all(directory).map_err(cache_error)?;
+    }
+    let partial_file = sibling_file(cache_file, "partial");
+    std::fs::write(&partial_file, content).map_err(cache_error)?;
+    std::fs::rename(&partial_file, cache_file).map_err(cache_error)
+}
+
+fn sibling_file(cache_file: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = cache_file.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(suffix);
+    cache_file.with_file_name(file_name)
+}
+
+/// The response headers a cached copy was fetched with, stored next to it.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+struct CacheValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn read(cache_file: &Path) -> Self {
+        std::fs::read(sibling_file(cache_file, "headers"))
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, cache_file: &Path) {
+        let headers_file = sibling_file(cache_file, "headers");
+        if let Err(err) = std::fs::write(&headers_file, serde_json::to_vec(self).unwrap()) {
+            log::warn!("Could not write {}: {}", headers_file.display(), err);
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum RemoteBookmarkFileError {
+    #[error("Could not fetch {0}: HTTP status {1}")]
+    Status(Url, u16),
+    #[error("Could not fetch {0}: {1}")]
+    Transport(Url, String),
+    #[error("Could not fetch {0}: the file is larger than {1} bytes")]
+    TooLarge(Url, u64),
+    #[error("Fetched invalid bookmarks from {0}: {1}")]
+    Invalid(Url, String),
+    #[error("Could not write cached bookmarks to {0}: {1}")]
+    Cache(PathBuf, #[source] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    use spectral::prelude::*;
+
+    use super::*;
+
+    /// Answers one connection per response, returning the request headers it received.
+    fn serve(responses: Vec<String>) -> (Url, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/bookmarks.yml",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let server = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let request = BufReader::new(stream.try_clone().unwrap())
+                        .lines()
+                        .map(Result::unwrap)
+                        .take_while(|line| !line.is_empty())
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    stream.write_all(response.as_bytes()).unwrap();
+                    request
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    fn response(status: &str, etag: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            etag,
+            body.len(),
+            body
+        )
+    }
+
+    #[test]
+    fn revalidates_the_cached_copy_and_keeps_it_when_the_remote_file_is_invalid() {
+        let wiki = "- name: Wiki\n  url: https://wiki.example.com\n";
+        let (url, server) = serve(vec![
+            response("200 OK", "\"v1\"", wiki),
+            response("304 Not Modified", "\"v1\"", ""),
+            response("200 OK", "\"v2\"", "- name: ["),
+        ]);
+        let directory = tempfile::tempdir().unwrap();
+        let cache_file = directory.path().join("cache").join("bookmarks.yml");
+        let source = BookmarkSource::remote(url, Some(cache_file.clone()), None, vec![]);
+
+        let fetched = refresh_remote_source(&source).unwrap();
+        let revalidated = refresh_remote_source(&source).unwrap();
+        let invalid = refresh_remote_source(&source);
+        let requests = server.join().unwrap();
+        let unreachable = refresh_remote_source(&source);
+
+        assert_that(&fetched).is_true();
+        assert_that(&revalidated).is_false();
+        assert_that(&invalid.unwrap_err().to_string()).contains("Fetched invalid bookmarks");
+        assert_that(&unreachable.is_err()).is_true();
+        assert_that(&requests[1].to_lowercase()).contains("if-none-match: \"v1\"");
+        assert_that(&std::fs::read_to_string(&cache_file).unwrap()).is_equal_to(wiki.to_string());
+    }
+
+    #[test]
+    fn rejects_remote_files_with_includes() {
+        let (url, server) = serve(vec![response(
+            "200 OK",
+            "\"v1\"",
+            "include: [\"../secrets.yml\"]\nbookmarks: []\n",
+        )]);
+        let directory = tempfile::tempdir().unwrap();
+        let cache_file = directory.path().join("bookmarks.yml");
+        let source = BookmarkSource::remote(url, Some(cache_file.clone()), None, vec![]);
+
+        let refreshed = refresh_remote_source(&source);
+        server.join().unwrap();
+
+        assert_that(&refreshed.unwrap_err().to_string()).contains("cannot include other files");
+        assert_that(&cache_file.exists()).is_false();
+    }
+
+    #[test]
+    fn reads_only_content_within_the_limit() {
+        assert_that(&read_to_limit(&b"1234"[..], 4).unwrap()).is_equal_to(Some(b"1234".to_vec()));
+        assert_that(&read_to_limit(&b"12345"[..], 4).unwrap()).is_none();
+    }
+}
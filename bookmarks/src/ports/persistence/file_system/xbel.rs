@@ -0,0 +1,449 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use url::Url;
+
+use crate::ports::persistence::file_system::bookmark_files::BookmarkFile;
+use crate::ports::persistence::file_system::persistence_bookmark::PersistenceBookmark;
+use crate::ports::persistence::file_system::persistence_entry::{
+    PersistenceEntry, PersistenceGroup,
+};
+
+const METADATA_OWNER: &str = "bookmarks-search-engine";
+
+pub(super) fn parse_xbel(content: &[u8]) -> Result<BookmarkFile, XbelError> {
+    let mut reader = Reader::from_reader(content);
+    reader.trim_text(true);
+    let mut buffer = vec![];
+    let mut elements: Vec<String> = vec![];
+    let mut frames: Vec<Frame> = vec![];
+    // Depth of the element stack at which metadata owned by another application started
+    let mut foreign_metadata: Option<usize> = None;
+
+    loop {
+        match reader.read_event(&mut buffer)? {
+            Event::Start(element) => {
+                let name = element_name(&element);
+                if foreign_metadata.is_none() {
+                    if is_foreign_metadata(&reader, &element, name.as_str())? {
+                        foreign_metadata = Some(elements.len());
+                    } else {
+                        start_element(&reader, &element, name.as_str(), &mut frames)?;
+                    }
+                }
+                elements.push(name);
+            }
+            Event::Empty(element) => {
+                let name = element_name(&element);
+                if foreign_metadata.is_none() {
+                    start_element(&reader, &element, name.as_str(), &mut frames)?;
+                    end_element(name.as_str(), &mut frames)?;
+                }
+            }
+            Event::End(_) => {
+                if let Some(name) = elements.pop() {
+                    if foreign_metadata == Some(elements.len()) {
+                        foreign_metadata = None;
+                    } else if foreign_metadata.is_none() {
+                        end_element(name.as_str(), &mut frames)?;
+                    }
+                }
+            }
+            Event::Text(text) if foreign_metadata.is_none() => {
+                let text = text.unescape_and_decode(&reader)?;
+                if let (Some(element), Some(frame)) = (elements.last(), frames.last_mut()) {
+                    frame.add_text(element.as_str(), text);
+                }
+            }
+            Event::CData(text) if foreign_metadata.is_none() => {
+                let text = reader.decode(&text)?.to_string();
+                if let (Some(element), Some(frame)) = (elements.last(), frames.last_mut()) {
+                    frame.add_text(element.as_str(), text);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+
+    match frames.pop() {
+        Some(Frame::Root(root)) => Ok(BookmarkFile::new(
+            root.tags.unwrap_or_default(),
+            root.include,
+            root.entries,
+        )),
+        _ => Err(XbelError::MissingRoot),
+    }
+}
+
+pub(super) fn write_xbel(bookmark_file: &BookmarkFile) -> String {
+    let mut xbel = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE xbel PUBLIC \"+//IDN python.org//DTD XML Bookmark Exchange Language 1.0//EN//XML\" \
+         \"http://pyxml.sourceforge.net/topics/dtds/xbel.dtd\">\n\
+         <xbel version=\"1.0\">\n",
+    );
+    if !bookmark_file.tags().is_empty() || !bookmark_file.include().is_empty() {
+        let mut metadata = tags_element(bookmark_file.tags());
+        bookmark_file.include().iter().for_each(|include| {
+            metadata.push_str(&format!(
+                "<include>{}</include>",
+                escape(&include.to_string_lossy())
+            ))
+        });
+        xbel.push_str(&format!("  {}\n", info_element(&metadata)));
+    }
+    write_entries(&mut xbel, bookmark_file.entries(), 1);
+    xbel.push_str("</xbel>\n");
+
+    xbel
+}
+
+fn write_entries(xbel: &mut String, entries: &[PersistenceEntry], depth: usize) {
+    let indent = "  ".repeat(depth);
+    entries.iter().for_each(|entry| match entry {
+        PersistenceEntry::Group(group) => {
+            xbel.push_str(&format!("{}<folder>\n", indent));
+            xbel.push_str(&format!(
+                "{}  <title>{}</title>\n",
+                indent,
+                escape(group.name())
+            ));
+            xbel.push_str(&format!(
+                "{}  {}\n",
+                indent,
+                info_element(&tags_element(group.tags()))
+            ));
+            write_entries(xbel, group.entries(), depth + 1);
+            xbel.push_str(&format!("{}</folder>\n", indent));
+        }
+        PersistenceEntry::Bookmark(bookmark) => {
+            let added = bookmark
+                .created_at()
+                .map(|created_at| {
+                    format!(
+                        " added=\"{}\"",
+                        created_at.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+                    )
+                })
+                .unwrap_or_default();
+            xbel.push_str(&format!(
+                "{}<bookmark href=\"{}\"{}>\n",
+                indent,
+                escape(bookmark.url().as_str()),
+                added
+            ));
+            xbel.push_str(&format!(
+                "{}  <title>{}</title>\n",
+                indent,
+                escape(bookmark.name())
+            ));
+            if !bookmark.tags().is_empty() || !bookmark.aliases().is_empty() {
+                let mut metadata = tags_element(bookmark.tags());
+                if !bookmark.aliases().is_empty() {
+                    metadata.push_str("<aliases>");
+                    bookmark.aliases().iter().for_each(|alias| {
+                        metadata.push_str(&format!("<alias>{}</alias>", escape(alias)))
+                    });
+                    metadata.push_str("</aliases>");
+                }
+                xbel.push_str(&format!("{}  {}\n", indent, info_element(&metadata)));
+            }
+            if let Some(description) = bookmark.description() {
+                xbel.push_str(&format!(
+                    "{}  <desc>{}</desc>\n",
+                    indent,
+                    escape(description)
+                ));
+            }
+            xbel.push_str(&format!("{}</bookmark>\n", indent));
+        }
+    });
+}
+
+fn info_element(metadata: &str) -> String {
+    format!(
+        "<info><metadata owner=\"{}\">{}</metadata></info>",
+        METADATA_OWNER, metadata
+    )
+}
+
+fn tags_element(tags: &[String]) -> String {
+    if tags.is_empty() {
+        return String::from("<tags/>");
+    }
+    let tags: String = tags
+        .iter()
+        .map(|tag| format!("<tag>{}</tag>", escape(tag)))
+        .collect();
+    format!("<tags>{}</tags>", tags)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn element_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name()).to_string()
+}
+
+fn attribute(
+    reader: &Reader<&[u8]>,
+    element: &BytesStart,
+    name: &[u8],
+) -> Result<Option<String>, XbelError> {
+    for attribute in element.attributes() {
+        let attribute = attribute?;
+        if attribute.key == name {
+            return Ok(Some(attribute.unescape_and_decode_value(reader)?));
+        }
+    }
+    Ok(None)
+}
+
+fn is_foreign_metadata(
+    reader: &Reader<&[u8]>,
+    element: &BytesStart,
+    name: &str,
+) -> Result<bool, XbelError> {
+    Ok(name == "metadata"
+        && attribute(reader, element, b"owner")?.as_deref() != Some(METADATA_OWNER))
+}
+
+fn start_element(
+    reader: &Reader<&[u8]>,
+    element: &BytesStart,
+    name: &str,
+    frames: &mut Vec<Frame>,
+) -> Result<(), XbelError> {
+    match name {
+        "xbel" => frames.push(Frame::Root(RootFrame::default())),
+        "folder" => frames.push(Frame::Folder(FolderFrame::default())),
+        "bookmark" => frames.push(Frame::Bookmark(BookmarkFrame {
+            href: attribute(reader, element, b"href")?.unwrap_or_default(),
+            added: attribute(reader, element, b"added")?,
+            ..BookmarkFrame::default()
+        })),
+        "tags" => {
+            if let Some(frame) = frames.last_mut() {
+                frame.start_tags();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn end_element(name: &str, frames: &mut Vec<Frame>) -> Result<(), XbelError> {
+    if name != "folder" && name != "bookmark" {
+        return Ok(());
+    }
+    let entry = match frames.pop() {
+        Some(Frame::Folder(folder)) => Some(folder.into_entry()),
+        Some(Frame::Bookmark(bookmark)) => bookmark.into_entry(),
+        _ => None,
+    };
+    if let (Some(entry), Some(parent)) = (entry, frames.last_mut()) {
+        parent.add_entry(entry);
+    }
+    Ok(())
+}
+
+enum Frame {
+    Root(RootFrame),
+    Folder(FolderFrame),
+    Bookmark(BookmarkFrame),
+}
+
+#[derive(Default)]
+struct RootFrame {
+    tags: Option<Vec<String>>,
+    include: Vec<PathBuf>,
+    entries: Vec<PersistenceEntry>,
+}
+
+#[derive(Default)]
+struct FolderFrame {
+    title: String,
+    tags: Option<Vec<String>>,
+    entries: Vec<PersistenceEntry>,
+}
+
+#[derive(Default)]
+struct BookmarkFrame {
+    href: String,
+    added: Option<String>,
+    title: String,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    aliases: Vec<String>,
+}
+
+impl Frame {
+    fn start_tags(&mut self) {
+        let tags = match self {
+            Frame::Root(root) => &mut root.tags,
+            Frame::Folder(folder) => &mut folder.tags,
+            Frame::Bookmark(bookmark) => &mut bookmark.tags,
+        };
+        tags.get_or_insert_with(Vec::new);
+    }
+
+    fn add_text(&mut self, element: &str, text: String) {
+        match (self, element) {
+            (Frame::Root(root), "tag") => root.tags.get_or_insert_with(Vec::new).push(text),
+            (Frame::Root(root), "include") => root.include.push(PathBuf::from(text)),
+            (Frame::Folder(folder), "title") => folder.title = text,
+            (Frame::Folder(folder), "tag") => folder.tags.get_or_insert_with(Vec::new).push(text),
+            (Frame::Bookmark(bookmark), "title") => bookmark.title = text,
+            (Frame::Bookmark(bookmark), "desc") => bookmark.description = Some(text),
+            (Frame::Bookmark(bookmark), "tag") => {
+                bookmark.tags.get_or_insert_with(Vec::new).push(text)
+            }
+            (Frame::Bookmark(bookmark), "alias") => bookmark.aliases.push(text),
+            _ => {}
+        }
+    }
+
+    fn add_entry(&mut self, entry: PersistenceEntry) {
+        match self {
+            Frame::Root(root) => root.entries.push(entry),
+            Frame::Folder(folder) => folder.entries.push(entry),
+            Frame::Bookmark(_) => {}
+        }
+    }
+}
+
+impl FolderFrame {
+    fn into_entry(self) -> PersistenceEntry {
+        let title = self.title;
+        let tags = self.tags.unwrap_or_else(|| vec![title.clone()]);
+        PersistenceEntry::Group(PersistenceGroup::new(title, tags, self.entries))
+    }
+}
+
+impl BookmarkFrame {
+    fn into_entry(self) -> Option<PersistenceEntry> {
+        let url = match Url::parse(&self.href) {
+            Ok(url) => url,
+            Err(_) => {
+                log::debug!("Skipping bookmark with invalid url: {}", self.href);
+                return None;
+            }
+        };
+        let created_at = self.added.and_then(|added| {
+            DateTime::parse_from_rfc3339(&added)
+                .map_err(|_| log::warn!("Ignoring invalid added date of {}: {}", url, added))
+                .ok()
+                .map(|created_at| created_at.with_timezone(&Utc))
+        });
+        let name = if self.title.is_empty() {
+            url.to_string()
+        } else {
+            self.title
+        };
+
+        Some(PersistenceEntry::Bookmark(PersistenceBookmark::new(
+            url,
+            name,
+            self.description,
+            self.tags.unwrap_or_default(),
+            self.aliases,
+            created_at,
+        )))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum XbelError {
+    #[error(transparent)]
+    Xml(#[from] quick_xml::Error),
+    #[error("Missing xbel root element")]
+    MissingRoot,
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    const YAML: &str = r#"---
+tags:
+  - team
+include:
+  - shared.yml
+bookmarks:
+  - group: Platform & Ops
+    tags:
+      - platform
+    bookmarks:
+      - url: "https://grafana.example.com/?a=1&b=2"
+        name: Grafana <prod>
+        description: Service dashboards
+        tags:
+          - metrics
+        aliases:
+          - gf
+        created_at: "2020-09-13T12:26:40Z"
+  - group: Untagged
+    bookmarks:
+      - url: "https://github.com/"
+        name: GitHub
+"#;
+
+    #[test]
+    fn round_trips_bookmark_files_without_loss() {
+        let bookmark_file: BookmarkFile = serde_yaml::from_str(YAML).unwrap();
+
+        let xbel = write_xbel(&bookmark_file);
+        let parsed = parse_xbel(xbel.as_bytes()).unwrap();
+
+        assert_that(&parsed.to_yaml().unwrap()).is_equal_to(YAML.to_string());
+    }
+
+    #[test]
+    fn uses_folder_titles_as_tags_for_foreign_files() {
+        let xbel = r#"<?xml version="1.0"?>
+<xbel version="1.0">
+  <folder>
+    <title>Docs</title>
+    <bookmark href="https://doc.rust-lang.org/"><title>Rust</title><desc>Language docs</desc></bookmark>
+  </folder>
+</xbel>"#;
+
+        let parsed = parse_xbel(xbel.as_bytes()).unwrap();
+
+        assert_that(&parsed.to_yaml().unwrap()).is_equal_to(
+            "---\n- group: Docs\n  tags:\n    - Docs\n  bookmarks:\n    - url: \"https://doc.rust-lang.org/\"\n      name: Rust\n      description: Language docs\n"
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn ignores_foreign_metadata_and_invalid_dates() {
+        let xbel = r#"<?xml version="1.0"?>
+<xbel version="1.0">
+  <bookmark href="https://doc.rust-lang.org/" added="13 September 2020">
+    <title>Rust</title>
+    <info>
+      <metadata owner="Mozilla"><tags><tag>imported</tag></tags></metadata>
+      <metadata owner="bookmarks-search-engine"><tags><tag>docs</tag></tags></metadata>
+    </info>
+  </bookmark>
+</xbel>"#;
+
+        let parsed = parse_xbel(xbel.as_bytes()).unwrap();
+
+        assert_that(&parsed.to_yaml().unwrap()).is_equal_to(
+            "---\n- url: \"https://doc.rust-lang.org/\"\n  name: Rust\n  tags:\n    - docs\n"
+                .to_string(),
+        );
+    }
+}
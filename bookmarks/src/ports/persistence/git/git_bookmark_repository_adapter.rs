@@ -0,0 +1,349 @@
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::domain::bookmark::{
+    Bookmark, BookmarkChange, BookmarkRepository, BookmarkRepositoryError, Editor,
+};
+use crate::domain::synonyms::Synonyms;
+use crate::ports::persistence::file_system::{
+    write_bookmarks_to_file, BookmarkFileFormat, BookmarkSource,
+    FileSystemBookmarkRepositoryAdapter, FileSystemBookmarkRepositoryAdapterError,
+};
+use crate::ports::persistence::git::git_working_copy::{GitError, GitWorkingCopy};
+
+const IMPORT_AUTHOR: &str = "bookmarks";
+
+/// Keeps the bookmarks in a single file of a git repository and commits every change to it,
+/// pushing the commits to `remote` when one is given.
+pub(crate) struct GitBookmarkRepositoryAdapter {
+    working_copy: GitWorkingCopy,
+    file: PathBuf,
+    format: BookmarkFileFormat,
+    remote: Option<String>,
+    branch: Option<String>,
+    bookmarks: FileSystemBookmarkRepositoryAdapter,
+}
+
+impl GitBookmarkRepositoryAdapter {
+    /// Fast-forwards the repository in `directory` to `remote`. This leaves the working copy as it
+    /// was when the pull fails.
+    pub(crate) fn pull(
+        directory: &Path,
+        remote: &str,
+        branch: Option<&str>,
+    ) -> Result<(), GitBookmarkRepositoryAdapterError> {
+        GitWorkingCopy::open(directory)?.pull(remote, branch)?;
+        log::info!("Pulled bookmarks from {}", remote);
+        Ok(())
+    }
+
+    pub(crate) fn open(
+        directory: &Path,
+        file: &Path,
+        remote: Option<&str>,
+        branch: Option<&str>,
+        synonyms: Synonyms,
+    ) -> Result<Self, GitBookmarkRepositoryAdapterError> {
+        let file = directory.join(file);
+        let format = BookmarkFileFormat::detect(file.as_path());
+        if !format.is_writable() {
+            return Err(GitBookmarkRepositoryAdapterError::ReadOnly(file, format));
+        }
+
+        Ok(GitBookmarkRepositoryAdapter {
+            working_copy: GitWorkingCopy::open(directory)?,
+            remote: remote.map(str::to_string),
+            branch: branch.map(str::to_string),
+            bookmarks: FileSystemBookmarkRepositoryAdapter::new(
+                vec![BookmarkSource::new(&file, vec![])],
+                synonyms,
+            ),
+            file,
+            format,
+        })
+    }
+
+    pub(crate) fn has_bookmark_file(&self) -> bool {
+        self.file.is_file()
+    }
+
+    pub(crate) fn initialise(&mut self) -> Result<(), GitBookmarkRepositoryAdapterError> {
+        Ok(self.bookmarks.initialise()?)
+    }
+
+    pub(crate) fn files(&self) -> Vec<PathBuf> {
+        self.bookmarks.files()
+    }
+
+    /// Writes the bookmarks to a new bookmark file and commits it.
+    pub(crate) fn import(
+        &self,
+        bookmarks: &[Bookmark],
+    ) -> Result<usize, GitBookmarkRepositoryAdapterError> {
+        write_bookmarks_to_file(&self.file, self.format, bookmarks)?;
+        self.working_copy.commit_file(
+            &self.file,
+            &Editor::new(IMPORT_AUTHOR.to_string(), None),
+            &format!("Import {} bookmarks", bookmarks.len()),
+        )?;
+        self.push();
+        Ok(bookmarks.len())
+    }
+
+    fn commit(&self, editor: &Editor, message: String) -> Result<(), BookmarkRepositoryError> {
+        if let Err(err) = self.working_copy.commit_file(&self.file, editor, &message) {
+            // The bookmark file was restored, so serve what it holds again
+            if let Err(err) = self.bookmarks.load() {
+                log::warn!("Could not reload {}: {}", self.file.display(), err);
+            }
+            return Err(GitBookmarkRepositoryAdapterError::from(err).into());
+        }
+        log::info!(
+            "Committed \"{}\" by {} in {}",
+            message,
+            editor.name(),
+            self.working_copy.directory().display()
+        );
+        self.push();
+        Ok(())
+    }
+
+    /// Commits that cannot be pushed are kept locally and pushed along with the next one.
+    fn push(&self) {
+        if let Some(remote) = self.remote.as_deref() {
+            if let Err(err) = self.working_copy.push(remote, self.branch.as_deref()) {
+                log::warn!("Could not push bookmarks to {}: {}", remote, err);
+            }
+        }
+    }
+}
+
+impl BookmarkRepository for GitBookmarkRepositoryAdapter {
+    fn get_all(&self) -> Result<Vec<Bookmark>, BookmarkRepositoryError> {
+        self.bookmarks.get_all()
+    }
+
+    fn get(&self, id: Uuid) -> Result<Bookmark, BookmarkRepositoryError> {
+        self.bookmarks.get(id)
+    }
+
+    fn save(
+        &self,
+        bookmark: &Bookmark,
+        editor: &Editor,
+    ) -> Result<BookmarkChange, BookmarkRepositoryError> {
+        let change = self.bookmarks.save(bookmark, editor)?;
+        let action = match change {
+            BookmarkChange::Added => "Add",
+            BookmarkChange::Updated => "Update",
+        };
+        self.commit(editor, change_message(action, bookmark))?;
+        Ok(change)
+    }
+
+    fn remove(&self, id: Uuid, editor: &Editor) -> Result<Bookmark, BookmarkRepositoryError> {
+        let bookmark = self.bookmarks.remove(id, editor)?;
+        self.commit(editor, change_message("Remove", &bookmark))?;
+        Ok(bookmark)
+    }
+}
+
+fn change_message(action: &str, bookmark: &Bookmark) -> String {
+    format!(
+        "{} bookmark \"{}\" ({})",
+        action,
+        bookmark.name(),
+        bookmark.url()
+    )
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum GitBookmarkRepositoryAdapterError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    FileSystem(#[from] FileSystemBookmarkRepositoryAdapterError),
+    #[error("Cannot keep bookmarks in {0}, {1} bookmark files cannot be written")]
+    ReadOnly(PathBuf, BookmarkFileFormat),
+}
+
+impl From<GitBookmarkRepositoryAdapterError> for BookmarkRepositoryError {
+    fn from(err: GitBookmarkRepositoryAdapterError) -> Self {
+        match err {
+            GitBookmarkRepositoryAdapterError::FileSystem(err) => err.into(),
+            GitBookmarkRepositoryAdapterError::Git(_)
+            | GitBookmarkRepositoryAdapterError::ReadOnly(_, _) => {
+                BookmarkRepositoryError::Unexpected(err.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+    use url::Url;
+
+    use super::*;
+
+    fn bookmark(url: &str, name: &str) -> Bookmark {
+        let url = Url::parse(url).unwrap();
+        Bookmark::new(Bookmark::id_for(&url), url, name.to_string(), None, vec![])
+    }
+
+    fn git_log(directory: &Path) -> String {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(directory)
+            .args(["log", "--format=%an <%ae>: %s"])
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn open(directory: &Path, remote: Option<&str>) -> GitBookmarkRepositoryAdapter {
+        GitBookmarkRepositoryAdapter::open(
+            directory,
+            Path::new("bookmarks.yml"),
+            remote,
+            None,
+            Synonyms::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn commits_every_change_as_the_editor() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut repository = open(directory.path(), None);
+        repository
+            .import(&[bookmark("https://grafana.example.com", "Grafana")])
+            .unwrap();
+        repository.initialise().unwrap();
+        let ada = Editor::new("Ada".to_string(), Some("ada@example.com".to_string()));
+
+        repository
+            .save(&bookmark("https://wiki.example.com", "Wiki"), &ada)
+            .unwrap();
+        let removed = repository
+            .remove(
+                bookmark("https://grafana.example.com", "Grafana").id(),
+                &Editor::new("Grace".to_string(), None),
+            )
+            .unwrap();
+
+        assert_that(removed.name()).is_equal_to("Grafana".to_string());
+        assert_that(&git_log(directory.path())).is_equal_to(
+            "Grace <>: Remove bookmark \"Grafana\" (https://grafana.example.com/)\n\
+             Ada <ada@example.com>: Add bookmark \"Wiki\" (https://wiki.example.com/)\n\
+             bookmarks <>: Import 1 bookmarks\n"
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn commits_only_the_bookmark_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut repository = open(directory.path(), None);
+        repository.import(&[]).unwrap();
+        repository.initialise().unwrap();
+        std::fs::write(directory.path().join("notes.txt"), "Not a bookmark").unwrap();
+
+        repository
+            .save(
+                &bookmark("https://wiki.example.com", "Wiki"),
+                &Editor::new("Ada".to_string(), None),
+            )
+            .unwrap();
+
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(directory.path())
+            .args(["status", "--porcelain"])
+            .output()
+            .unwrap();
+        assert_that(&String::from_utf8(status.stdout).unwrap())
+            .is_equal_to("?? notes.txt\n".to_string());
+    }
+
+    #[test]
+    fn restores_the_bookmark_file_when_the_commit_fails() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut repository = open(directory.path(), None);
+        repository
+            .import(&[bookmark("https://grafana.example.com", "Grafana")])
+            .unwrap();
+        repository.initialise().unwrap();
+        let bookmark_file = directory.path().join("bookmarks.yml");
+        let committed = std::fs::read_to_string(&bookmark_file).unwrap();
+        let hook = directory.path().join(".git/hooks/pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::os::unix::fs::PermissionsExt::from_mode(0o755))
+            .unwrap();
+
+        let saved = repository.save(
+            &bookmark("https://wiki.example.com", "Wiki"),
+            &Editor::new("Ada".to_string(), None),
+        );
+
+        assert_that(&saved).is_err();
+        assert_that(&std::fs::read_to_string(&bookmark_file).unwrap()).is_equal_to(committed);
+        assert_that(&repository.get_all().unwrap()).has_length(1);
+    }
+
+    #[test]
+    fn pulls_from_the_remote_only_when_asked_to() {
+        let upstream = tempfile::tempdir().unwrap();
+        open(upstream.path(), None)
+            .import(&[bookmark("https://grafana.example.com", "Grafana")])
+            .unwrap();
+        let directory = tempfile::tempdir().unwrap();
+        let remote = upstream.path().to_string_lossy().to_string();
+        GitBookmarkRepositoryAdapter::pull(directory.path(), &remote, None).unwrap();
+        open(upstream.path(), None)
+            .import(&[
+                bookmark("https://grafana.example.com", "Grafana"),
+                bookmark("https://wiki.example.com", "Wiki"),
+            ])
+            .unwrap();
+
+        let mut opened = open(directory.path(), Some(&remote));
+        opened.initialise().unwrap();
+        GitBookmarkRepositoryAdapter::pull(directory.path(), &remote, None).unwrap();
+        let mut pulled = open(directory.path(), Some(&remote));
+        pulled.initialise().unwrap();
+
+        assert_that(&opened.get_all().unwrap()).has_length(1);
+        assert_that(&pulled.get_all().unwrap()).has_length(2);
+    }
+
+    #[test]
+    fn pushes_every_commit_to_the_remote() {
+        let upstream = tempfile::tempdir().unwrap();
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(upstream.path())
+            .args(["init", "--quiet", "--bare"])
+            .status()
+            .unwrap();
+        let directory = tempfile::tempdir().unwrap();
+        let remote = upstream.path().to_string_lossy().to_string();
+        let mut repository = open(directory.path(), Some(&remote));
+        repository.import(&[]).unwrap();
+        repository.initialise().unwrap();
+
+        repository
+            .save(
+                &bookmark("https://wiki.example.com", "Wiki"),
+                &Editor::new("Ada".to_string(), None),
+            )
+            .unwrap();
+
+        assert_that(&git_log(upstream.path())).is_equal_to(
+            "Ada <>: Add bookmark \"Wiki\" (https://wiki.example.com/)\n\
+             bookmarks <>: Import 0 bookmarks\n"
+                .to_string(),
+        );
+    }
+}
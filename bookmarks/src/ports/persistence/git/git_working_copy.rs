@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::domain::bookmark::Editor;
+
+/// A local git repository, driven through the `git` command line.
+pub(super) struct GitWorkingCopy {
+    directory: PathBuf,
+}
+
+impl GitWorkingCopy {
+    /// Opens the repository in `directory`, creating the directory and initialising a repository
+    /// there if needed.
+    pub(super) fn open(directory: &Path) -> Result<Self, GitError> {
+        std::fs::create_dir_all(directory)
+            .map_err(|err| GitError::Directory(directory.to_path_buf(), err))?;
+        let working_copy = GitWorkingCopy {
+            directory: directory.to_path_buf(),
+        };
+        if !directory.join(".git").exists() {
+            working_copy.run(&["init", "--quiet"])?;
+            log::info!("Initialised git repository in {}", directory.display());
+        }
+        Ok(working_copy)
+    }
+
+    pub(super) fn directory(&self) -> &Path {
+        self.directory.as_path()
+    }
+
+    pub(super) fn pull(&self, remote: &str, branch: Option<&str>) -> Result<(), GitError> {
+        let mut args = vec!["pull", "--ff-only", "--quiet", remote];
+        args.extend(branch);
+        self.run(&args).map(|_| ())
+    }
+
+    pub(super) fn push(&self, remote: &str, branch: Option<&str>) -> Result<(), GitError> {
+        let refspec = branch
+            .map(|branch| format!("HEAD:{}", branch))
+            .unwrap_or_else(|| "HEAD".to_string());
+        self.run(&["push", "--quiet", remote, &refspec]).map(|_| ())
+    }
+
+    /// Commits the changes to `file` as `editor`, returning false when it did not change. Other
+    /// changes in the working copy are left alone. When the commit fails, `file` is restored to its
+    /// last committed state.
+    pub(super) fn commit_file(
+        &self,
+        file: &Path,
+        editor: &Editor,
+        message: &str,
+    ) -> Result<bool, GitError> {
+        let path = file
+            .strip_prefix(&self.directory)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .to_string();
+        self.run(&["add", "--", &path])?;
+        if self
+            .run(&["diff", "--cached", "--quiet", "--", &path])
+            .is_ok()
+        {
+            return Ok(false);
+        }
+        let name = format!("user.name={}", editor.name());
+        let email = format!(
+            "user.email={}",
+            editor.email().map(String::as_str).unwrap_or_default()
+        );
+        if let Err(err) = self.run(&[
+            "-c",
+            &name,
+            "-c",
+            &email,
+            "commit",
+            "--quiet",
+            "--message",
+            message,
+            "--",
+            &path,
+        ]) {
+            self.restore(&path);
+            return Err(err);
+        }
+        Ok(true)
+    }
+
+    fn restore(&self, path: &str) {
+        let restored = self
+            .run(&["reset", "--quiet", "--", path])
+            .and_then(|_| self.run(&["checkout", "--", path]));
+        if let Err(err) = restored {
+            log::warn!("Could not restore {}: {}", path, err);
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, GitError> {
+        let command = format!("git {}", args.join(" "));
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.directory)
+            .args(args)
+            .output()
+            .map_err(|err| GitError::Spawn(command.clone(), err))?;
+        if !output.status.success() {
+            return Err(GitError::Failed(
+                command,
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum GitError {
+    #[error("Could not create git repository directory {0}: {1}")]
+    Directory(PathBuf, #[source] std::io::Error),
+    #[error("Could not run `{0}`: {1}")]
+    Spawn(String, #[source] std::io::Error),
+    #[error("`{0}` failed: {1}")]
+    Failed(String, String),
+}
@@ -0,0 +1,4 @@
+pub(crate) use git_bookmark_repository_adapter::*;
+
+mod git_bookmark_repository_adapter;
+mod git_working_copy;
@@ -1 +1,6 @@
+pub(crate) use configured_bookmark_repository::*;
+
+mod configured_bookmark_repository;
 pub(crate) mod file_system;
+pub(crate) mod git;
+pub(crate) mod sqlite;
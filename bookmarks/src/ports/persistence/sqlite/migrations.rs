@@ -0,0 +1,61 @@
+use rusqlite::Connection;
+
+const MIGRATIONS: [&str; 3] = [
+    "
+    CREATE TABLE bookmarks (
+        id TEXT PRIMARY KEY NOT NULL,
+        url TEXT NOT NULL UNIQUE,
+        name TEXT NOT NULL,
+        description TEXT,
+        created_at TEXT
+    );
+    CREATE TABLE bookmark_tags (
+        bookmark_id TEXT NOT NULL REFERENCES bookmarks (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (bookmark_id, position)
+    );
+    CREATE TABLE bookmark_aliases (
+        bookmark_id TEXT NOT NULL REFERENCES bookmarks (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        alias TEXT NOT NULL,
+        PRIMARY KEY (bookmark_id, position)
+    );
+    CREATE TABLE bookmark_groups (
+        bookmark_id TEXT NOT NULL REFERENCES bookmarks (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        PRIMARY KEY (bookmark_id, position)
+    );
+    CREATE INDEX bookmark_tags_tag ON bookmark_tags (tag);
+",
+    "
+    ALTER TABLE bookmarks ADD COLUMN updated_at TEXT;
+    CREATE TABLE bookmark_visits (
+        bookmark_id TEXT PRIMARY KEY NOT NULL,
+        visit_count INTEGER NOT NULL,
+        last_visited_at TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE bookmark_visit_log (
+        bookmark_id TEXT NOT NULL,
+        visited_at INTEGER NOT NULL,
+        query TEXT
+    );
+    CREATE INDEX bookmark_visit_log_visited_at ON bookmark_visit_log (visited_at);
+",
+];
+
+pub(super) fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let current_version: usize =
+        connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current_version) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", &(index as i64 + 1))?;
+        transaction.commit()?;
+        log::info!("Applied bookmark database migration {}", index + 1);
+    }
+    Ok(())
+}
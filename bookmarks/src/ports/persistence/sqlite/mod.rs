@@ -0,0 +1,6 @@
+pub(crate) use sqlite_bookmark_repository_adapter::*;
+pub(crate) use sqlite_bookmark_visit_repository_adapter::*;
+
+mod migrations;
+mod sqlite_bookmark_repository_adapter;
+mod sqlite_bookmark_visit_repository_adapter;
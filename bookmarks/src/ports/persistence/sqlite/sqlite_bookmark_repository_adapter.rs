@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use url::Url;
+use uuid::Uuid;
+
+use crate::domain::bookmark::{
+    Bookmark, BookmarkChange, BookmarkRepository, BookmarkRepositoryError, Editor,
+};
+use crate::ports::persistence::sqlite::migrations::migrate;
+
+pub(crate) struct SqliteBookmarkRepositoryAdapter {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteBookmarkRepositoryAdapter {
+    pub(crate) fn open(database_path: &Path) -> Result<Self, SqliteBookmarkRepositoryAdapterError> {
+        Ok(SqliteBookmarkRepositoryAdapter {
+            connection: Mutex::new(open_database(Some(database_path))?),
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> Result<bool, SqliteBookmarkRepositoryAdapterError> {
+        let count: i64 =
+            self.connection()?
+                .query_row("SELECT COUNT(*) FROM bookmarks", [], |row| row.get(0))?;
+        Ok(count == 0)
+    }
+
+    pub(crate) fn import(
+        &self,
+        bookmarks: &[Bookmark],
+    ) -> Result<usize, SqliteBookmarkRepositoryAdapterError> {
+        let mut connection = self.connection()?;
+        let transaction = connection.transaction()?;
+        for bookmark in bookmarks {
+            save_bookmark(&transaction, bookmark)?;
+        }
+        transaction.commit()?;
+        Ok(bookmarks.len())
+    }
+
+    fn save_one(
+        &self,
+        bookmark: &Bookmark,
+    ) -> Result<BookmarkChange, SqliteBookmarkRepositoryAdapterError> {
+        let mut connection = self.connection()?;
+        let transaction = connection.transaction()?;
+        let existing: Option<i64> = transaction
+            .query_row(
+                "SELECT 1 FROM bookmarks WHERE id = ?",
+                [bookmark.id().to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        save_bookmark(&transaction, bookmark)?;
+        transaction.commit()?;
+        Ok(match existing {
+            Some(_) => BookmarkChange::Updated,
+            None => BookmarkChange::Added,
+        })
+    }
+
+    fn delete(&self, id: Uuid) -> Result<usize, SqliteBookmarkRepositoryAdapterError> {
+        Ok(self
+            .connection()?
+            .execute("DELETE FROM bookmarks WHERE id = ?", [id.to_string()])?)
+    }
+
+    fn connection(
+        &self,
+    ) -> Result<MutexGuard<'_, Connection>, SqliteBookmarkRepositoryAdapterError> {
+        self.connection
+            .lock()
+            .map_err(|_| SqliteBookmarkRepositoryAdapterError::Poisoned)
+    }
+
+    fn load(
+        &self,
+        id: Option<Uuid>,
+    ) -> Result<Vec<Bookmark>, SqliteBookmarkRepositoryAdapterError> {
+        let connection = self.connection()?;
+        let id = id.map(|id| id.to_string());
+        let mut tags = values_by_bookmark(&connection, "bookmark_tags", "tag", id.as_deref())?;
+        let mut aliases =
+            values_by_bookmark(&connection, "bookmark_aliases", "alias", id.as_deref())?;
+        let mut groups = values_by_bookmark(&connection, "bookmark_groups", "name", id.as_deref())?;
+
+        let rows = connection
+            .prepare(
+                "SELECT id, url, name, description, created_at, updated_at FROM bookmarks \
+                 WHERE ?1 IS NULL OR id = ?1 ORDER BY name",
+            )?
+            .query_map([&id], |row| {
+                Ok(BookmarkRow {
+                    id: row.get(0)?,
+                    url: row.get(1)?,
+                    name: row.get(2)?,
+                    description: row.get(3)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<BookmarkRow>, rusqlite::Error>>()?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id = Uuid::parse_str(&row.id)
+                    .map_err(|_| SqliteBookmarkRepositoryAdapterError::Corrupt(row.id.clone()))?;
+                let url = Url::parse(&row.url)
+                    .map_err(|_| SqliteBookmarkRepositoryAdapterError::Corrupt(row.url.clone()))?;
+                Ok(Bookmark::new(
+                    id,
+                    url,
+                    row.name,
+                    row.description,
+                    tags.remove(&row.id).unwrap_or_default(),
+                )
+                .with_aliases(aliases.remove(&row.id).unwrap_or_default())
+                .with_group_path(groups.remove(&row.id).unwrap_or_default())
+                .with_created_at(parse_timestamp(row.created_at)?)
+                .with_updated_at(parse_timestamp(row.updated_at)?))
+            })
+            .collect()
+    }
+}
+
+impl BookmarkRepository for SqliteBookmarkRepositoryAdapter {
+    fn get_all(&self) -> Result<Vec<Bookmark>, BookmarkRepositoryError> {
+        Ok(self.load(None)?)
+    }
+
+    fn get(&self, id: Uuid) -> Result<Bookmark, BookmarkRepositoryError> {
+        self.load(Some(id))?
+            .pop()
+            .ok_or(BookmarkRepositoryError::BookmarkNotFound(id))
+    }
+
+    fn save(
+        &self,
+        bookmark: &Bookmark,
+        _editor: &Editor,
+    ) -> Result<BookmarkChange, BookmarkRepositoryError> {
+        Ok(self.save_one(bookmark)?)
+    }
+
+    fn remove(&self, id: Uuid, _editor: &Editor) -> Result<Bookmark, BookmarkRepositoryError> {
+        let bookmark = self.get(id)?;
+        self.delete(id)?;
+        Ok(bookmark)
+    }
+}
+
+/// Opens and migrates a database file, or a private in-memory database without a path.
+pub(super) fn open_database(
+    database_path: Option<&Path>,
+) -> Result<Connection, SqliteBookmarkRepositoryAdapterError> {
+    let mut connection = match database_path {
+        Some(database_path) => Connection::open(database_path).map_err(|err| {
+            SqliteBookmarkRepositoryAdapterError::Open(database_path.to_path_buf(), err)
+        })?,
+        None => Connection::open_in_memory()?,
+    };
+    connection.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")?;
+    migrate(&mut connection).map_err(SqliteBookmarkRepositoryAdapterError::Migration)?;
+    Ok(connection)
+}
+
+struct BookmarkRow {
+    id: String,
+    url: String,
+    name: String,
+    description: Option<String>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+pub(super) fn parse_timestamp(
+    timestamp: Option<String>,
+) -> Result<Option<DateTime<Utc>>, SqliteBookmarkRepositoryAdapterError> {
+    timestamp
+        .map(|timestamp| {
+            DateTime::parse_from_rfc3339(&timestamp)
+                .map(|parsed| parsed.with_timezone(&Utc))
+                .map_err(|_| SqliteBookmarkRepositoryAdapterError::Corrupt(timestamp))
+        })
+        .transpose()
+}
+
+fn save_bookmark(
+    transaction: &Transaction,
+    bookmark: &Bookmark,
+) -> Result<(), SqliteBookmarkRepositoryAdapterError> {
+    let id = bookmark.id().to_string();
+    let existing_id: Option<String> = transaction
+        .query_row(
+            "SELECT id FROM bookmarks WHERE url = ?",
+            [bookmark.url().as_str()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(existing_id) = existing_id.filter(|existing_id| *existing_id != id) {
+        transaction.execute("DELETE FROM bookmarks WHERE id = ?", [existing_id])?;
+    }
+
+    transaction.execute(
+        "INSERT INTO bookmarks (id, url, name, description, created_at, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6) \
+         ON CONFLICT (id) DO UPDATE SET url = ?2, name = ?3, description = ?4, \
+         created_at = COALESCE(?5, created_at), updated_at = COALESCE(?6, updated_at)",
+        params![
+            id,
+            bookmark.url().as_str(),
+            bookmark.name(),
+            bookmark.description(),
+            bookmark
+                .created_at()
+                .map(|created_at| created_at.to_rfc3339()),
+            bookmark
+                .updated_at()
+                .map(|updated_at| updated_at.to_rfc3339()),
+        ],
+    )?;
+    save_values(transaction, "bookmark_tags", "tag", &id, bookmark.tags())?;
+    save_values(
+        transaction,
+        "bookmark_aliases",
+        "alias",
+        &id,
+        bookmark.aliases(),
+    )?;
+    save_values(
+        transaction,
+        "bookmark_groups",
+        "name",
+        &id,
+        bookmark.group_path(),
+    )?;
+    Ok(())
+}
+
+fn save_values(
+    transaction: &Transaction,
+    table: &str,
+    column: &str,
+    bookmark_id: &str,
+    values: &[String],
+) -> rusqlite::Result<()> {
+    transaction.execute(
+        &format!("DELETE FROM {} WHERE bookmark_id = ?", table),
+        [bookmark_id],
+    )?;
+    let mut statement = transaction.prepare(&format!(
+        "INSERT INTO {} (bookmark_id, position, {}) VALUES (?, ?, ?)",
+        table, column
+    ))?;
+    for (position, value) in values.iter().enumerate() {
+        statement.execute(params![bookmark_id, position as i64, value])?;
+    }
+    Ok(())
+}
+
+fn values_by_bookmark(
+    connection: &Connection,
+    table: &str,
+    column: &str,
+    bookmark_id: Option<&str>,
+) -> rusqlite::Result<HashMap<String, Vec<String>>> {
+    let mut values: HashMap<String, Vec<String>> = HashMap::new();
+    connection
+        .prepare(&format!(
+            "SELECT bookmark_id, {} FROM {} WHERE ?1 IS NULL OR bookmark_id = ?1 \
+             ORDER BY bookmark_id, position",
+            column, table
+        ))?
+        .query_map([bookmark_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<(String, String)>, rusqlite::Error>>()?
+        .into_iter()
+        .for_each(|(bookmark_id, value)| values.entry(bookmark_id).or_default().push(value));
+    Ok(values)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum SqliteBookmarkRepositoryAdapterError {
+    #[error("Could not open bookmark database {0}: {1}")]
+    Open(PathBuf, #[source] rusqlite::Error),
+    #[error("Could not migrate bookmark database: {0}")]
+    Migration(#[source] rusqlite::Error),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Invalid value in bookmark database: {0}")]
+    Corrupt(String),
+    #[error("Bookmark database connection is poisoned")]
+    Poisoned,
+}
+
+impl From<SqliteBookmarkRepositoryAdapterError> for BookmarkRepositoryError {
+    fn from(err: SqliteBookmarkRepositoryAdapterError) -> Self {
+        BookmarkRepositoryError::Unexpected(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use spectral::prelude::*;
+
+    use super::*;
+
+    fn grafana(name: &str) -> Bookmark {
+        Bookmark::new(
+            Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://grafana.example.com/"),
+            Url::parse("https://grafana.example.com").unwrap(),
+            name.to_string(),
+            Some("Dashboards".to_string()),
+            vec!["metrics".to_string(), "ops".to_string()],
+        )
+        .with_aliases(vec!["gf".to_string()])
+        .with_group_path(vec!["Platform".to_string()])
+        .with_created_at(Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap()))
+    }
+
+    #[test]
+    fn imports_bookmarks_and_replaces_them_on_reimport() {
+        let directory = tempfile::tempdir().unwrap();
+        let database_path = directory.path().join("bookmarks.db");
+        let repository = SqliteBookmarkRepositoryAdapter::open(&database_path).unwrap();
+        assert_that(&repository.is_empty().unwrap()).is_true();
+
+        repository.import(&[grafana("Grafana")]).unwrap();
+        repository.import(&[grafana("Grafana prod")]).unwrap();
+
+        let reopened = SqliteBookmarkRepositoryAdapter::open(&database_path).unwrap();
+        let bookmarks = reopened.get_all().unwrap();
+        assert_that(&bookmarks).has_length(1);
+        let bookmark = reopened.get(bookmarks[0].id()).unwrap();
+        assert_that(bookmark.name()).is_equal_to("Grafana prod".to_string());
+        assert_that(bookmark.tags()).is_equal_to(vec!["metrics".to_string(), "ops".to_string()]);
+        assert_that(bookmark.aliases()).is_equal_to(vec!["gf".to_string()]);
+        assert_that(bookmark.group_path()).is_equal_to(vec!["Platform".to_string()]);
+        assert_that(&bookmark.created_at())
+            .is_equal_to(Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap()));
+    }
+
+    #[test]
+    fn saves_and_removes_single_bookmarks() {
+        let directory = tempfile::tempdir().unwrap();
+        let repository =
+            SqliteBookmarkRepositoryAdapter::open(&directory.path().join("bookmarks.db")).unwrap();
+        let editor = Editor::new("Ada".to_string(), None);
+        let renamed = Bookmark::new(
+            grafana("Grafana").id(),
+            grafana("Grafana").url().clone(),
+            "Grafana prod".to_string(),
+            None,
+            vec![],
+        );
+
+        let added = repository.save(&grafana("Grafana"), &editor).unwrap();
+        let updated = repository.save(&renamed, &editor).unwrap();
+        let bookmark = repository.get(renamed.id()).unwrap();
+        let removed = repository.remove(renamed.id(), &editor).unwrap();
+
+        assert_that(&added).is_equal_to(BookmarkChange::Added);
+        assert_that(&updated).is_equal_to(BookmarkChange::Updated);
+        assert_that(bookmark.name()).is_equal_to("Grafana prod".to_string());
+        assert_that(&bookmark.created_at())
+            .is_equal_to(Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap()));
+        assert_that(removed.name()).is_equal_to("Grafana prod".to_string());
+        assert_that(&repository.is_empty().unwrap()).is_true();
+        assert_that(&matches!(
+            repository.remove(renamed.id(), &editor),
+            Err(BookmarkRepositoryError::BookmarkNotFound(_))
+        ))
+        .is_true();
+    }
+
+    #[test]
+    fn rolls_back_an_import_that_fails_part_way() {
+        let directory = tempfile::tempdir().unwrap();
+        let repository =
+            SqliteBookmarkRepositoryAdapter::open(&directory.path().join("bookmarks.db")).unwrap();
+        repository
+            .connection()
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER reject_wiki BEFORE INSERT ON bookmarks \
+                 WHEN NEW.name = 'Wiki' BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+            )
+            .unwrap();
+        let wiki = Bookmark::new(
+            Uuid::new_v4(),
+            Url::parse("https://wiki.example.com").unwrap(),
+            "Wiki".to_string(),
+            None,
+            vec![],
+        );
+
+        let result = repository.import(&[grafana("Grafana"), wiki]);
+
+        assert_that(&result.is_err()).is_true();
+        assert_that(&repository.is_empty().unwrap()).is_true();
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rusqlite::{params, Connection};
+use uuid::Uuid;
+
+use crate::domain::bookmark::{
+    BookmarkVisit, BookmarkVisitRepository, BookmarkVisitRepositoryError, BookmarkVisits,
+};
+use crate::ports::persistence::sqlite::sqlite_bookmark_repository_adapter::{
+    open_database, parse_timestamp, SqliteBookmarkRepositoryAdapterError,
+};
+
+/// Logs every visit in `bookmark_visit_log` and counts them per bookmark in `bookmark_visits`,
+/// kept in memory when no database is configured. Logged visits older than the retention period
+/// are deleted, the counts are kept.
+pub(crate) struct SqliteBookmarkVisitRepositoryAdapter {
+    connection: Mutex<Connection>,
+    retention: Option<Duration>,
+}
+
+impl SqliteBookmarkVisitRepositoryAdapter {
+    pub(crate) fn open(
+        database_path: Option<&Path>,
+        retention: Option<Duration>,
+    ) -> Result<Self, SqliteBookmarkRepositoryAdapterError> {
+        let visits = SqliteBookmarkVisitRepositoryAdapter {
+            connection: Mutex::new(open_database(database_path)?),
+            retention,
+        };
+        let pruned = visits.prune(&*visits.connection()?, Utc::now())?;
+        if pruned > 0 {
+            log::info!("Deleted {} visits past their retention period", pruned);
+        }
+        Ok(visits)
+    }
+
+    fn connection(
+        &self,
+    ) -> Result<MutexGuard<'_, Connection>, SqliteBookmarkRepositoryAdapterError> {
+        self.connection
+            .lock()
+            .map_err(|_| SqliteBookmarkRepositoryAdapterError::Poisoned)
+    }
+
+    fn prune(&self, connection: &Connection, now: DateTime<Utc>) -> rusqlite::Result<usize> {
+        match self.retention {
+            Some(retention) => connection.execute(
+                "DELETE FROM bookmark_visit_log WHERE visited_at < ?",
+                [(now - retention).timestamp()],
+            ),
+            None => Ok(0),
+        }
+    }
+
+    fn save(&self, visit: &BookmarkVisit) -> Result<(), SqliteBookmarkRepositoryAdapterError> {
+        let mut connection = self.connection()?;
+        let transaction = connection.transaction()?;
+        let id = visit.bookmark_id().to_string();
+        transaction.execute(
+            "INSERT INTO bookmark_visit_log (bookmark_id, visited_at, query) VALUES (?1, ?2, ?3)",
+            params![id, visit.visited_at().timestamp(), visit.query()],
+        )?;
+        transaction.execute(
+            "INSERT INTO bookmark_visits (bookmark_id, visit_count, last_visited_at) \
+             VALUES (?1, 1, ?2) \
+             ON CONFLICT (bookmark_id) DO UPDATE SET visit_count = visit_count + 1, \
+             last_visited_at = ?2",
+            params![id, visit.visited_at().to_rfc3339()],
+        )?;
+        self.prune(&transaction, visit.visited_at())?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Loads the visit counts of the given bookmarks, or of all of them.
+    fn load(
+        &self,
+        ids: Option<&[Uuid]>,
+    ) -> Result<HashMap<Uuid, BookmarkVisits>, SqliteBookmarkRepositoryAdapterError> {
+        let connection = self.connection()?;
+        let mut statement = connection.prepare(
+            "SELECT bookmark_id, visit_count, last_visited_at FROM bookmark_visits \
+             WHERE ?1 IS NULL OR bookmark_id = ?1",
+        )?;
+        let ids = match ids {
+            Some(ids) => ids.iter().map(|id| Some(id.to_string())).collect(),
+            None => vec![None],
+        };
+        let mut rows = vec![];
+        for id in ids {
+            rows.extend(
+                statement
+                    .query_map([id], |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, i64>(1)?,
+                            row.get::<_, String>(2)?,
+                        ))
+                    })?
+                    .collect::<Result<Vec<(String, i64, String)>, rusqlite::Error>>()?,
+            );
+        }
+
+        rows.into_iter()
+            .map(|(id, count, last_visited_at)| {
+                let id = Uuid::parse_str(&id)
+                    .map_err(|_| SqliteBookmarkRepositoryAdapterError::Corrupt(id.clone()))?;
+                Ok((
+                    id,
+                    BookmarkVisits::new(count as u64, parse_timestamp(Some(last_visited_at))?),
+                ))
+            })
+            .collect()
+    }
+
+    fn load_log(&self) -> Result<Vec<BookmarkVisit>, SqliteBookmarkRepositoryAdapterError> {
+        let connection = self.connection()?;
+        let rows = connection
+            .prepare(
+                "SELECT bookmark_id, visited_at, query FROM bookmark_visit_log \
+                 ORDER BY visited_at",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<(String, i64, Option<String>)>, rusqlite::Error>>()?;
+
+        rows.into_iter()
+            .map(|(id, visited_at, query)| {
+                let bookmark_id = Uuid::parse_str(&id)
+                    .map_err(|_| SqliteBookmarkRepositoryAdapterError::Corrupt(id.clone()))?;
+                let visited_at = Utc.timestamp_opt(visited_at, 0).single().ok_or_else(|| {
+                    SqliteBookmarkRepositoryAdapterError::Corrupt(visited_at.to_string())
+                })?;
+                Ok(BookmarkVisit::new(bookmark_id, visited_at, query))
+            })
+            .collect()
+    }
+}
+
+impl BookmarkVisitRepository for SqliteBookmarkVisitRepositoryAdapter {
+    fn record_visit(&self, visit: &BookmarkVisit) -> Result<(), BookmarkVisitRepositoryError> {
+        Ok(self.save(visit)?)
+    }
+
+    fn get_all(&self) -> Result<HashMap<Uuid, BookmarkVisits>, BookmarkVisitRepositoryError> {
+        Ok(self.load(None)?)
+    }
+
+    fn get(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, BookmarkVisits>, BookmarkVisitRepositoryError> {
+        Ok(self.load(Some(ids))?)
+    }
+
+    fn get_visits(&self) -> Result<Vec<BookmarkVisit>, BookmarkVisitRepositoryError> {
+        Ok(self.load_log()?)
+    }
+}
+
+impl From<SqliteBookmarkRepositoryAdapterError> for BookmarkVisitRepositoryError {
+    fn from(err: SqliteBookmarkRepositoryAdapterError) -> Self {
+        BookmarkVisitRepositoryError::Unexpected(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use spectral::prelude::*;
+
+    use super::*;
+
+    fn grafana() -> Uuid {
+        Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://grafana.example.com/")
+    }
+
+    #[test]
+    fn counts_visits_across_reopening() {
+        let directory = tempfile::tempdir().unwrap();
+        let database_path = directory.path().join("visits.db");
+        let first_visit = Utc.timestamp_opt(1_600_000_000, 0).unwrap();
+        let last_visit = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        let visits =
+            SqliteBookmarkVisitRepositoryAdapter::open(Some(&database_path), None).unwrap();
+        visits
+            .record_visit(&BookmarkVisit::new(grafana(), first_visit, None))
+            .unwrap();
+        visits
+            .record_visit(&BookmarkVisit::new(
+                grafana(),
+                last_visit,
+                Some("dash".to_string()),
+            ))
+            .unwrap();
+        drop(visits);
+
+        let reopened =
+            SqliteBookmarkVisitRepositoryAdapter::open(Some(&database_path), None).unwrap();
+        assert_that(&reopened.get_all().unwrap().get(&grafana()).copied())
+            .is_equal_to(Some(BookmarkVisits::new(2, Some(last_visit))));
+        assert_that(&reopened.get_visits().unwrap()).is_equal_to(vec![
+            BookmarkVisit::new(grafana(), first_visit, None),
+            BookmarkVisit::new(grafana(), last_visit, Some("dash".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn gets_visit_counts_of_the_given_bookmarks_only() {
+        let visits = SqliteBookmarkVisitRepositoryAdapter::open(None, None).unwrap();
+        let wiki = Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://wiki.example.com/");
+        let unvisited = Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://runbooks.example.com/");
+        let visited_at = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        for id in [grafana(), wiki] {
+            visits
+                .record_visit(&BookmarkVisit::new(id, visited_at, None))
+                .unwrap();
+        }
+        let found = visits.get(&[wiki, unvisited]).unwrap();
+
+        assert_that(&found.keys().collect::<Vec<_>>()).is_equal_to(vec![&wiki]);
+        assert_that(&found[&wiki]).is_equal_to(BookmarkVisits::new(1, Some(visited_at)));
+    }
+
+    #[test]
+    fn deletes_logged_visits_past_the_retention_period_but_keeps_counting_them() {
+        let visits =
+            SqliteBookmarkVisitRepositoryAdapter::open(None, Some(Duration::days(30))).unwrap();
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        for visited_at in [now - Duration::days(40), now - Duration::days(10), now] {
+            visits
+                .record_visit(&BookmarkVisit::new(grafana(), visited_at, None))
+                .unwrap();
+        }
+
+        assert_that(&visits.get_visits().unwrap()).has_length(2);
+        assert_that(&visits.get_all().unwrap()[&grafana()].count()).is_equal_to(3);
+    }
+}
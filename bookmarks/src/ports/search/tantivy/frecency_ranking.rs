@@ -0,0 +1,43 @@
+use chrono::Duration;
+
+/// How much visits lift a bookmark above equally relevant ones. A weight of zero ranks by text
+/// relevance alone.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct FrecencyConfig {
+    weight: f32,
+    half_life_days: u32,
+}
+
+impl FrecencyConfig {
+    #[cfg(test)]
+    pub(crate) fn new(weight: f32, half_life_days: u32) -> Self {
+        FrecencyConfig {
+            weight,
+            half_life_days,
+        }
+    }
+
+    pub(crate) fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    pub(crate) fn half_life(&self) -> Duration {
+        Duration::days(self.half_life_days.into())
+    }
+}
+
+impl Default for FrecencyConfig {
+    fn default() -> Self {
+        FrecencyConfig {
+            weight: 0.5,
+            half_life_days: 30,
+        }
+    }
+}
+
+/// Scales the text relevance logarithmically, so that a handful of recent visits matter but
+/// hundreds of them cannot bury a much better text match.
+pub(super) fn frecency_boost(weight: f32, frecency: f64) -> f32 {
+    1.0 + weight * (frecency as f32).ln_1p()
+}
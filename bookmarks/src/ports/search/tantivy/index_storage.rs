@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use tantivy::directory::MmapDirectory;
+use tantivy::schema::Schema;
+use tantivy::Index;
+
+use crate::ports::search::tantivy::text_analysis::TextAnalysisConfig;
+use crate::ports::search::tantivy::TantivyBookmarkSearchEngineAdapterError;
+
+const SCHEMA_VERSION: u32 = 9;
+/// The index is kept in a subdirectory of its own, as it is deleted on rebuild and the configured
+/// directory may hold other files.
+const INDEX_SUBDIRECTORY: &str = "tantivy";
+const NEXT_INDEX_SUBDIRECTORY: &str = "tantivy.next";
+const RETIRED_INDEX_SUBDIRECTORY: &str = "tantivy.old";
+
+#[derive(serde::Serialize)]
+struct IndexFingerprint<'a> {
+    schema_version: u32,
+    text_analysis: &'a TextAnalysisConfig,
+}
+
+pub(super) fn index_fingerprint(text_analysis: &TextAnalysisConfig) -> String {
+    serde_json::to_string(&IndexFingerprint {
+        schema_version: SCHEMA_VERSION,
+        text_analysis,
+    })
+    .unwrap()
+}
+
+/// Opens a copy of the index to build on, so the index being served is only replaced once the
+/// new one is complete, see [`publish_index`]. The copy starts out empty unless the served index
+/// has the same fingerprint.
+pub(super) fn open_index(
+    index_directory: Option<&Path>,
+    schema: Schema,
+    fingerprint: &str,
+) -> Result<Index, TantivyBookmarkSearchEngineAdapterError> {
+    let index_directory = match index_directory {
+        Some(index_directory) => index_directory,
+        None => return Ok(Index::create_in_ram(schema)),
+    };
+    let served = index_directory.join(INDEX_SUBDIRECTORY);
+    let next = index_directory.join(NEXT_INDEX_SUBDIRECTORY);
+
+    if next.exists() {
+        std::fs::remove_dir_all(&next)?;
+    }
+    std::fs::create_dir_all(&next)?;
+    if open_compatible_index(&served, &schema, fingerprint).is_some() {
+        copy_index_files(&served, &next)?;
+        if let Some(index) = open_compatible_index(&next, &schema, fingerprint) {
+            return Ok(index);
+        }
+    }
+
+    log::info!(
+        "Rebuilding search index in {} as it is missing or out of date",
+        served.display()
+    );
+    std::fs::remove_dir_all(&next)?;
+    std::fs::create_dir_all(&next)?;
+    let directory = MmapDirectory::open(&next).map_err(tantivy::TantivyError::from)?;
+    Ok(Index::create(directory, schema)?)
+}
+
+/// Replaces the served index with one built by [`open_index`] and opens it where it is served
+/// from. The index it replaces is kept aside until [`remove_retired_index`], and is put back if
+/// the new index cannot be opened.
+pub(super) fn publish_index<T, F>(
+    index_directory: Option<&Path>,
+    index: Index,
+    open: F,
+) -> Result<T, TantivyBookmarkSearchEngineAdapterError>
+where
+    F: FnOnce(Index) -> Result<T, TantivyBookmarkSearchEngineAdapterError>,
+{
+    let index_directory = match index_directory {
+        Some(index_directory) => index_directory,
+        None => return open(index),
+    };
+    drop(index);
+    let served = index_directory.join(INDEX_SUBDIRECTORY);
+    let retired = index_directory.join(RETIRED_INDEX_SUBDIRECTORY);
+
+    if retired.exists() {
+        std::fs::remove_dir_all(&retired)?;
+    }
+    let retiring = served.exists();
+    if retiring {
+        std::fs::rename(&served, &retired)?;
+    }
+    let published = std::fs::rename(index_directory.join(NEXT_INDEX_SUBDIRECTORY), &served)
+        .map_err(TantivyBookmarkSearchEngineAdapterError::from)
+        .and_then(|_| Ok(Index::open_in_dir(&served)?))
+        .and_then(open);
+    if published.is_err() && retiring {
+        if served.exists() {
+            std::fs::remove_dir_all(&served)?;
+        }
+        std::fs::rename(&retired, &served)?;
+    }
+    published
+}
+
+/// Deletes the index replaced by [`publish_index`], once the index replacing it is being served.
+pub(crate) fn remove_retired_index(index_directory: Option<&Path>) {
+    let retired = match index_directory {
+        Some(index_directory) => index_directory.join(RETIRED_INDEX_SUBDIRECTORY),
+        None => return,
+    };
+    if retired.exists() {
+        if let Err(err) = std::fs::remove_dir_all(&retired) {
+            log::warn!("Could not remove {}: {}", retired.display(), err);
+        }
+    }
+}
+
+/// Copies the index files, leaving out the locks held by the index being served.
+fn copy_index_files(from: &Path, to: &Path) -> Result<(), std::io::Error> {
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && !entry.file_name().to_string_lossy().ends_with(".lock") {
+            std::fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn open_compatible_index(
+    index_directory: &Path,
+    schema: &Schema,
+    fingerprint: &str,
+) -> Option<Index> {
+    let directory = MmapDirectory::open(index_directory).ok()?;
+    if !Index::exists(&directory).ok()? {
+        return None;
+    }
+    let index = Index::open(directory).ok()?;
+    let metas = index.load_metas().ok()?;
+
+    if &index.schema() == schema && metas.payload.as_deref() == Some(fingerprint) {
+        Some(index)
+    } else {
+        None
+    }
+}
@@ -1,3 +1,10 @@
+pub(crate) use frecency_ranking::*;
+pub(crate) use index_storage::remove_retired_index;
 pub(crate) use tantivy_bookmark_search_engine_adapter::*;
+pub(crate) use text_analysis::*;
 
+mod frecency_ranking;
+mod index_storage;
+mod synonym_expansion;
 mod tantivy_bookmark_search_engine_adapter;
+mod text_analysis;
@@ -0,0 +1,117 @@
+use crate::domain::synonyms::Synonyms;
+
+pub(super) fn expand_synonyms(term: &str, synonyms: &Synonyms) -> String {
+    split_query(term)
+        .iter()
+        .map(|part| expand_part(part, synonyms))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn expand_part(part: &str, synonyms: &Synonyms) -> String {
+    let (occur, word) = match part.chars().next() {
+        Some(prefix @ '+') | Some(prefix @ '-') => (Some(prefix), &part[1..]),
+        _ => (None, part),
+    };
+    let (field, word) = match word.split_once(':') {
+        Some((field, word)) if is_plain_word(field) => (Some(field), word),
+        _ => (None, word),
+    };
+
+    if !is_plain_word(word) {
+        return part.to_string();
+    }
+
+    match synonyms.variants(word) {
+        Some(variants) => {
+            let alternatives = variants
+                .iter()
+                .map(|variant| match field {
+                    Some(field) => format!("{}:{}", field, quote_if_phrase(variant)),
+                    None => quote_if_phrase(variant),
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+            match occur {
+                Some(prefix) => format!("{}({})", prefix, alternatives),
+                None => format!("({})", alternatives),
+            }
+        }
+        None => part.to_string(),
+    }
+}
+
+fn is_plain_word(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+fn quote_if_phrase(variant: &str) -> String {
+    if variant.contains(char::is_whitespace) {
+        format!("\"{}\"", variant)
+    } else {
+        variant.to_string()
+    }
+}
+
+fn split_query(term: &str) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    term.chars().for_each(|c| {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        if c.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    });
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn expands_plain_words_into_alternatives_and_leaves_the_rest() {
+        let synonyms = Synonyms::new(vec![vec![
+            "kubernetes".to_string(),
+            "k8s".to_string(),
+            "container orchestration".to_string(),
+        ]]);
+
+        let expanded = expand_synonyms("+k8s \"k8s docs\" dashboard", &synonyms);
+
+        assert_that(&expanded).is_equal_to(
+            "+(kubernetes k8s \"container orchestration\") \"k8s docs\" dashboard".to_string(),
+        );
+    }
+
+    #[test]
+    fn expands_field_qualified_words_within_their_field() {
+        let synonyms = Synonyms::new(vec![vec![
+            "kubernetes".to_string(),
+            "container orchestration".to_string(),
+        ]]);
+
+        let expanded = expand_synonyms("-tags:kubernetes tags:\"kubernetes\"", &synonyms);
+
+        assert_that(&expanded).is_equal_to(
+            "-(tags:kubernetes tags:\"container orchestration\") tags:\"kubernetes\"".to_string(),
+        );
+    }
+}
@@ -1,33 +1,90 @@
-use tantivy::collector::TopDocs;
-use tantivy::query::{QueryParser, QueryParserError};
-use tantivy::schema::{Field, Schema, STORED, TEXT};
-use tantivy::{Document, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyError};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use tantivy::collector::{Count, TopDocs};
+use tantivy::fastfield::FastFieldReader;
+use tantivy::query::{Query, QueryParser, QueryParserError, TermQuery};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, FAST, STORED, STRING,
+};
+use tantivy::{
+    DocAddress, DocId, Document, Index, IndexReader, IndexWriter, ReloadPolicy, Score, Searcher,
+    SegmentId, SegmentReader, TantivyError, Term,
+};
+use url::Url;
 use uuid::Uuid;
 
 use crate::domain::bookmark::{
-    Bookmark, BookmarkRepository, BookmarkRepositoryError, BookmarkSearchEngine,
-    BookmarkSearchEngineError,
+    Bookmark, BookmarkSearchEngine, BookmarkSearchEngineError, BookmarkVisit, Frecency,
 };
+use crate::domain::synonyms::Synonyms;
+use crate::ports::search::tantivy::frecency_ranking::{frecency_boost, FrecencyConfig};
+use crate::ports::search::tantivy::index_storage::{index_fingerprint, open_index, publish_index};
+use crate::ports::search::tantivy::synonym_expansion::expand_synonyms;
+use crate::ports::search::tantivy::text_analysis::{TextAnalysisConfig, BOOKMARK_TEXT_ANALYZER};
+
+const WRITER_HEAP_SIZE: usize = 50_000_000;
+const SEARCH_LIMIT: usize = 10;
 
-pub(crate) struct TantivyBookmarkSearchEngineAdapter<BR: BookmarkRepository> {
-    bookmark_repository: BR,
+pub(crate) struct TantivyBookmarkSearchEngineAdapter {
+    text_analysis: TextAnalysisConfig,
+    synonyms: Synonyms,
+    index_directory: Option<PathBuf>,
+    frecency_weight: f32,
+    frecency: RwLock<Frecency>,
     initialised_engine: Option<InitialisedEngine>,
 }
 
-impl<BR: BookmarkRepository> TantivyBookmarkSearchEngineAdapter<BR> {
-    pub(crate) fn new(bookmark_repository: BR) -> Self {
+impl TantivyBookmarkSearchEngineAdapter {
+    pub(crate) fn new(
+        text_analysis: TextAnalysisConfig,
+        synonyms: Synonyms,
+        index_directory: Option<PathBuf>,
+    ) -> Self {
+        let frecency = FrecencyConfig::default();
         TantivyBookmarkSearchEngineAdapter {
-            bookmark_repository,
+            text_analysis,
+            synonyms,
+            index_directory,
+            frecency_weight: frecency.weight(),
+            frecency: RwLock::new(Frecency::new(frecency.half_life())),
             initialised_engine: None,
         }
     }
 
+    pub(crate) fn with_frecency(self, frecency: &FrecencyConfig) -> Self {
+        TantivyBookmarkSearchEngineAdapter {
+            frecency_weight: frecency.weight(),
+            frecency: RwLock::new(Frecency::new(frecency.half_life())),
+            ..self
+        }
+    }
+
     fn schema() -> Schema {
+        let text_options = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(BOOKMARK_TEXT_ANALYZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored();
+
         let mut schema_builder = Schema::builder();
-        schema_builder.add_text_field("id", STORED);
-        schema_builder.add_text_field("name", TEXT);
-        schema_builder.add_text_field("description", TEXT);
-        schema_builder.add_text_field("tags", TEXT);
+        schema_builder.add_text_field("id", STRING | STORED);
+        schema_builder.add_text_field("content_hash", STORED);
+        schema_builder.add_text_field("url", STORED);
+        schema_builder.add_text_field("name", text_options.clone());
+        schema_builder.add_text_field("description", text_options.clone());
+        schema_builder.add_text_field("tags", text_options.clone());
+        schema_builder.add_text_field("aliases", text_options.clone());
+        schema_builder.add_text_field("group_path", text_options);
+        schema_builder.add_date_field("created_at", STORED);
+        schema_builder.add_date_field("updated_at", STORED);
+        schema_builder.add_u64_field("visit_key_high", FAST);
+        schema_builder.add_u64_field("visit_key_low", FAST);
 
         schema_builder.build()
     }
@@ -39,6 +96,11 @@ impl<BR: BookmarkRepository> TantivyBookmarkSearchEngineAdapter<BR> {
     ) {
         let mut bookmark_doc = Document::default();
         bookmark_doc.add_text(bookmark_document_fields.id, bookmark.id());
+        bookmark_doc.add_text(
+            bookmark_document_fields.content_hash,
+            content_hash(bookmark),
+        );
+        bookmark_doc.add_text(bookmark_document_fields.url, bookmark.url().as_str());
         bookmark_doc.add_text(bookmark_document_fields.name, bookmark.name());
         if let Some(description) = bookmark.description() {
             bookmark_doc.add_text(bookmark_document_fields.description, description);
@@ -47,6 +109,21 @@ impl<BR: BookmarkRepository> TantivyBookmarkSearchEngineAdapter<BR> {
             .tags()
             .iter()
             .for_each(|tag| bookmark_doc.add_text(bookmark_document_fields.tags, tag.to_string()));
+        bookmark.aliases().iter().for_each(|alias| {
+            bookmark_doc.add_text(bookmark_document_fields.aliases, alias.to_string())
+        });
+        bookmark.group_path().iter().for_each(|group| {
+            bookmark_doc.add_text(bookmark_document_fields.group_path, group.to_string())
+        });
+        if let Some(created_at) = bookmark.created_at() {
+            bookmark_doc.add_date(bookmark_document_fields.created_at, &created_at);
+        }
+        if let Some(updated_at) = bookmark.updated_at() {
+            bookmark_doc.add_date(bookmark_document_fields.updated_at, &updated_at);
+        }
+        let (visit_key_high, visit_key_low) = visit_key(bookmark.id());
+        bookmark_doc.add_u64(bookmark_document_fields.visit_key_high, visit_key_high);
+        bookmark_doc.add_u64(bookmark_document_fields.visit_key_low, visit_key_low);
         index_writer.add_document(bookmark_doc);
     }
 
@@ -55,123 +132,308 @@ impl<BR: BookmarkRepository> TantivyBookmarkSearchEngineAdapter<BR> {
         bookmarks: Vec<Bookmark>,
     ) -> Result<(), TantivyBookmarkSearchEngineAdapterError> {
         let schema = Self::schema();
-        let index = Index::create_in_ram(schema.clone());
-        let mut index_writer = index.writer(50_000_000).unwrap();
+        let fingerprint = index_fingerprint(&self.text_analysis);
+        let index = open_index(
+            self.index_directory.as_deref(),
+            schema.clone(),
+            &fingerprint,
+        )?;
+        index
+            .tokenizers()
+            .register(BOOKMARK_TEXT_ANALYZER, self.text_analysis.text_analyzer());
+        let mut index_writer = index.writer(WRITER_HEAP_SIZE)?;
 
         let document_fields: BookmarkDocumentFields = BookmarkDocumentFields::from(&schema);
+        let mut indexed_hashes = Self::indexed_content_hashes(&index, &document_fields)?;
 
         bookmarks.iter().for_each(|bookmark| {
+            let id = bookmark.id().to_string();
+            if indexed_hashes.remove(&id) == Some(content_hash(bookmark)) {
+                return;
+            }
+            index_writer.delete_term(Term::from_field_text(document_fields.id, &id));
             Self::add_document(&mut index_writer, &document_fields, bookmark);
         });
+        indexed_hashes.keys().for_each(|stale_id| {
+            index_writer.delete_term(Term::from_field_text(document_fields.id, stale_id));
+        });
 
-        index_writer.commit()?;
+        let mut prepared_commit = index_writer.prepare_commit()?;
+        prepared_commit.set_payload(&fingerprint);
+        prepared_commit.commit()?;
+        index_writer.wait_merging_threads()?;
 
-        let reader = index
-            .reader_builder()
-            .reload_policy(ReloadPolicy::OnCommit)
-            .try_into()
-            .unwrap();
+        let text_analyzer = self.text_analysis.text_analyzer();
+        let (index, reader) = publish_index(self.index_directory.as_deref(), index, |index| {
+            index
+                .tokenizers()
+                .register(BOOKMARK_TEXT_ANALYZER, text_analyzer);
+            let reader = index
+                .reader_builder()
+                .reload_policy(ReloadPolicy::OnCommit)
+                .try_into()?;
+            Ok((index, reader))
+        })?;
 
         self.initialised_engine = Some(InitialisedEngine {
             reader,
             index,
+            write_lock: Mutex::new(()),
             document_fields,
+            fingerprint,
         });
 
         Ok(())
     }
 
-    fn initialised_engine(&self) -> &InitialisedEngine {
-        &self
-            .initialised_engine
-            .as_ref()
-            .ok_or(TantivyBookmarkSearchEngineAdapterError::NotInitialised)
-            .unwrap()
+    fn indexed_content_hashes(
+        index: &Index,
+        document_fields: &BookmarkDocumentFields,
+    ) -> Result<HashMap<String, String>, TantivyBookmarkSearchEngineAdapterError> {
+        let searcher = index.reader()?.searcher();
+        let mut hashes = HashMap::new();
+
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader()?;
+            for doc_id in segment_reader.doc_ids_alive() {
+                let document = store_reader.get(doc_id)?;
+                let id = stored_text(&document, document_fields.id)?;
+                let hash = stored_text(&document, document_fields.content_hash)?;
+                hashes.insert(id, hash);
+            }
+        }
+
+        Ok(hashes)
     }
-}
 
-impl<BR: BookmarkRepository> BookmarkSearchEngine for TantivyBookmarkSearchEngineAdapter<BR> {
-    fn search(&self, term: String) -> Result<Vec<Bookmark>, BookmarkSearchEngineError> {
+    fn write<F>(&self, operation: F) -> Result<(), TantivyBookmarkSearchEngineAdapterError>
+    where
+        F: FnOnce(&mut IndexWriter, &BookmarkDocumentFields),
+    {
+        let initialised_engine = self.initialised_engine();
+        let _write_guard = initialised_engine
+            .write_lock
+            .lock()
+            .map_err(|_| TantivyBookmarkSearchEngineAdapterError::WriterPoisoned)?;
+        let mut index_writer = initialised_engine.index.writer(WRITER_HEAP_SIZE)?;
+
+        operation(&mut index_writer, &initialised_engine.document_fields);
+
+        let mut prepared_commit = index_writer.prepare_commit()?;
+        prepared_commit.set_payload(&initialised_engine.fingerprint);
+        prepared_commit.commit()?;
+        index_writer.wait_merging_threads()?;
+        initialised_engine.reader.reload()?;
+
+        Ok(())
+    }
+
+    fn ensure_indexed(&self, id: Uuid) -> Result<(), TantivyBookmarkSearchEngineAdapterError> {
+        let initialised_engine = self.initialised_engine();
+        let query = TermQuery::new(
+            id_term(&initialised_engine.document_fields, id),
+            IndexRecordOption::Basic,
+        );
+        let count = initialised_engine
+            .reader
+            .searcher()
+            .search(&query, &Count)?;
+
+        if count == 0 {
+            return Err(TantivyBookmarkSearchEngineAdapterError::BookmarkNotIndexed(
+                id,
+            ));
+        }
+        Ok(())
+    }
+
+    fn replace_document(
+        &self,
+        bookmark: &Bookmark,
+    ) -> Result<(), TantivyBookmarkSearchEngineAdapterError> {
+        self.write(|index_writer, document_fields| {
+            index_writer.delete_term(id_term(document_fields, bookmark.id()));
+            Self::add_document(index_writer, document_fields, bookmark);
+        })
+    }
+
+    /// Multiplies the text relevance of visited bookmarks by their frecency boost.
+    fn top_docs(
+        &self,
+        searcher: &Searcher,
+        query: &dyn Query,
+        document_fields: &BookmarkDocumentFields,
+    ) -> Result<Vec<(Score, DocAddress)>, TantivyBookmarkSearchEngineAdapterError> {
+        let boosts = Arc::new(self.frecency_boosts());
+        if boosts.is_empty() {
+            return Ok(searcher.search(query, &TopDocs::with_limit(SEARCH_LIMIT))?);
+        }
+        let visit_keys = Arc::new(
+            searcher
+                .segment_readers()
+                .iter()
+                .map(|segment_reader| {
+                    let fast_fields = segment_reader.fast_fields();
+                    Ok((
+                        segment_reader.segment_id(),
+                        (
+                            fast_fields.u64(document_fields.visit_key_high)?,
+                            fast_fields.u64(document_fields.visit_key_low)?,
+                        ),
+                    ))
+                })
+                .collect::<Result<HashMap<SegmentId, VisitKeyReaders>, TantivyError>>()?,
+        );
+        let ranking =
+            TopDocs::with_limit(SEARCH_LIMIT).tweak_score(move |segment_reader: &SegmentReader| {
+                let segment_visit_keys = visit_keys.get(&segment_reader.segment_id()).cloned();
+                let boosts = boosts.clone();
+                move |doc: DocId, score: Score| match &segment_visit_keys {
+                    Some((high, low)) => {
+                        score
+                            * boosts
+                                .get(&(high.get(doc), low.get(doc)))
+                                .copied()
+                                .unwrap_or(1.0)
+                    }
+                    None => score,
+                }
+            });
+        Ok(searcher.search(query, &ranking)?)
+    }
+
+    fn frecency_boosts(&self) -> HashMap<(u64, u64), f32> {
+        if self.frecency_weight <= 0.0 {
+            return HashMap::new();
+        }
+        self.frecency
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .scores(Utc::now())
+            .into_iter()
+            .map(|(id, frecency)| {
+                (
+                    visit_key(id),
+                    frecency_boost(self.frecency_weight, frecency),
+                )
+            })
+            .collect()
+    }
+
+    fn parse_query(
+        &self,
+        term: &str,
+    ) -> Result<Box<dyn Query>, TantivyBookmarkSearchEngineAdapterError> {
         let initialised_engine = self.initialised_engine();
-        let reader = &initialised_engine.reader;
-        let index = &initialised_engine.index;
         let document_fields = &initialised_engine.document_fields;
         let search_fields = vec![
             document_fields.name,
             document_fields.description,
             document_fields.description,
             document_fields.tags,
+            document_fields.aliases,
+            document_fields.group_path,
         ];
 
-        let searcher = reader.searcher();
-        let query_parser = QueryParser::for_index(&index, search_fields);
-        let query = query_parser
-            .parse_query(term.as_str())
-            .map_err(TantivyBookmarkSearchEngineAdapterError::from)?;
-        let top_docs = searcher
-            .search(&query, &TopDocs::with_limit(10))
-            .map_err(TantivyBookmarkSearchEngineAdapterError::from)?;
+        let query_parser = QueryParser::for_index(&initialised_engine.index, search_fields);
+        Ok(query_parser.parse_query(expand_synonyms(term, &self.synonyms).as_str())?)
+    }
+
+    fn initialised_engine(&self) -> &InitialisedEngine {
+        self.initialised_engine
+            .as_ref()
+            .ok_or(TantivyBookmarkSearchEngineAdapterError::NotInitialised)
+            .unwrap()
+    }
+}
+
+impl BookmarkSearchEngine for TantivyBookmarkSearchEngineAdapter {
+    fn search(&self, term: String) -> Result<Vec<Bookmark>, BookmarkSearchEngineError> {
+        let initialised_engine = self.initialised_engine();
+        let document_fields = &initialised_engine.document_fields;
+
+        let searcher = initialised_engine.reader.searcher();
+        let query = self.parse_query(term.as_str())?;
+        let top_docs = self.top_docs(&searcher, query.as_ref(), document_fields)?;
 
-        let ids: Vec<Uuid> = top_docs
+        Ok(top_docs
             .iter()
             .map(|(_score, doc_address)| {
-                let retrieved_doc = searcher
-                    .doc(*doc_address)
-                    .map_err(TantivyBookmarkSearchEngineAdapterError::from)
-                    .unwrap();
-                let id = retrieved_doc
-                    .field_values()
-                    .iter()
-                    .find_map(|val| Uuid::parse_str(val.value().text().unwrap()).ok());
-
-                id.ok_or(TantivyBookmarkSearchEngineAdapterError::MissingRequiredField)
-                    .unwrap()
+                let retrieved_doc = searcher.doc(*doc_address)?;
+                bookmark_from_document(&retrieved_doc, document_fields)
             })
-            .collect();
+            .collect::<Result<Vec<Bookmark>, TantivyBookmarkSearchEngineAdapterError>>()?)
+    }
 
-        Ok(ids
+    fn index(&self, bookmark: &Bookmark) -> Result<(), BookmarkSearchEngineError> {
+        Ok(self.replace_document(bookmark)?)
+    }
+
+    fn update(&self, bookmark: &Bookmark) -> Result<(), BookmarkSearchEngineError> {
+        self.ensure_indexed(bookmark.id())?;
+        Ok(self.replace_document(bookmark)?)
+    }
+
+    fn remove(&self, id: Uuid) -> Result<(), BookmarkSearchEngineError> {
+        self.ensure_indexed(id)?;
+        Ok(self.write(|index_writer, document_fields| {
+            index_writer.delete_term(id_term(document_fields, id));
+        })?)
+    }
+
+    fn record_visits(&self, visits: &[BookmarkVisit]) -> Result<(), BookmarkSearchEngineError> {
+        let mut frecency = self
+            .frecency
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        visits
             .iter()
-            .map(|id| {
-                self.bookmark_repository
-                    .get(*id)
-                    .map_err(TantivyBookmarkSearchEngineAdapterError::from)
-            })
-            .collect::<Result<Vec<Bookmark>, TantivyBookmarkSearchEngineAdapterError>>()?)
+            .for_each(|visit| frecency.add_visit(visit.bookmark_id(), visit.visited_at()));
+        Ok(())
     }
 }
 
+type VisitKeyReaders = (FastFieldReader<u64>, FastFieldReader<u64>);
+
 struct InitialisedEngine {
     reader: IndexReader,
     index: Index,
+    write_lock: Mutex<()>,
     document_fields: BookmarkDocumentFields,
+    fingerprint: String,
 }
 
 struct BookmarkDocumentFields {
     id: Field,
+    content_hash: Field,
+    url: Field,
     name: Field,
     description: Field,
     tags: Field,
-}
-
-impl BookmarkDocumentFields {
-    fn new(id: Field, name: Field, description: Field, tags: Field) -> Self {
-        BookmarkDocumentFields {
-            id,
-            name,
-            description,
-            tags,
-        }
-    }
+    aliases: Field,
+    group_path: Field,
+    created_at: Field,
+    updated_at: Field,
+    visit_key_high: Field,
+    visit_key_low: Field,
 }
 
 impl From<&Schema> for BookmarkDocumentFields {
     fn from(schema: &Schema) -> Self {
-        BookmarkDocumentFields::new(
-            schema.get_field("id").unwrap(),
-            schema.get_field("name").unwrap(),
-            schema.get_field("description").unwrap(),
-            schema.get_field("tags").unwrap(),
-        )
+        BookmarkDocumentFields {
+            id: schema.get_field("id").unwrap(),
+            content_hash: schema.get_field("content_hash").unwrap(),
+            url: schema.get_field("url").unwrap(),
+            name: schema.get_field("name").unwrap(),
+            description: schema.get_field("description").unwrap(),
+            tags: schema.get_field("tags").unwrap(),
+            aliases: schema.get_field("aliases").unwrap(),
+            group_path: schema.get_field("group_path").unwrap(),
+            created_at: schema.get_field("created_at").unwrap(),
+            updated_at: schema.get_field("updated_at").unwrap(),
+            visit_key_high: schema.get_field("visit_key_high").unwrap(),
+            visit_key_low: schema.get_field("visit_key_low").unwrap(),
+        }
     }
 }
 
@@ -181,14 +443,18 @@ pub(crate) enum TantivyBookmarkSearchEngineAdapterError {
     NotInitialised,
     #[error(transparent)]
     Tantivy(#[from] TantivyError),
+    #[error("Could not prepare index directory: {0}")]
+    IndexDirectory(#[from] std::io::Error),
     #[error(transparent)]
     QueryParse(#[from] QueryParserError),
     #[error("Retrieved document is missing a required field")]
     MissingRequiredField,
-    #[error(transparent)]
-    BookmarkNotFound(BookmarkRepositoryError),
-    #[error(transparent)]
-    BookmarkRepository(BookmarkRepositoryError),
+    #[error("Retrieved document has an invalid field value")]
+    InvalidFieldValue,
+    #[error("Index writer is unusable after a failed write")]
+    WriterPoisoned,
+    #[error("Could not find indexed Bookmark with id: {0}")]
+    BookmarkNotIndexed(Uuid),
 }
 
 impl From<TantivyBookmarkSearchEngineAdapterError> for BookmarkSearchEngineError {
@@ -196,32 +462,605 @@ impl From<TantivyBookmarkSearchEngineAdapterError> for BookmarkSearchEngineError
         match err {
             TantivyBookmarkSearchEngineAdapterError::NotInitialised
             | TantivyBookmarkSearchEngineAdapterError::MissingRequiredField
-            | TantivyBookmarkSearchEngineAdapterError::BookmarkNotFound(
-                BookmarkRepositoryError::Unexpected(_),
-            )
+            | TantivyBookmarkSearchEngineAdapterError::InvalidFieldValue
+            | TantivyBookmarkSearchEngineAdapterError::WriterPoisoned
             | TantivyBookmarkSearchEngineAdapterError::Tantivy(_)
-            | TantivyBookmarkSearchEngineAdapterError::BookmarkRepository(_) => {
+            | TantivyBookmarkSearchEngineAdapterError::IndexDirectory(_) => {
                 BookmarkSearchEngineError::Unexpected(format!("{}", err))
             }
             TantivyBookmarkSearchEngineAdapterError::QueryParse(_) => {
                 BookmarkSearchEngineError::InvalidQuery
             }
-            TantivyBookmarkSearchEngineAdapterError::BookmarkNotFound(
-                BookmarkRepositoryError::BookmarkNotFound(id),
-            ) => BookmarkSearchEngineError::BookmarkNotFound(id),
+            TantivyBookmarkSearchEngineAdapterError::BookmarkNotIndexed(id) => {
+                BookmarkSearchEngineError::BookmarkNotFound(id)
+            }
         }
     }
 }
 
-impl From<BookmarkRepositoryError> for TantivyBookmarkSearchEngineAdapterError {
-    fn from(err: BookmarkRepositoryError) -> Self {
-        match err {
-            BookmarkRepositoryError::BookmarkNotFound(_) => {
-                TantivyBookmarkSearchEngineAdapterError::BookmarkNotFound(err)
-            }
-            BookmarkRepositoryError::Unexpected(_) => {
-                TantivyBookmarkSearchEngineAdapterError::BookmarkRepository(err)
-            }
-        }
+fn id_term(document_fields: &BookmarkDocumentFields, id: Uuid) -> Term {
+    Term::from_field_text(document_fields.id, &id.to_string())
+}
+
+/// Bookmarks are looked up by id when ranking, through two fast fields holding the halves of the id.
+fn visit_key(id: Uuid) -> (u64, u64) {
+    let id = id.as_u128();
+    ((id >> 64) as u64, id as u64)
+}
+
+fn bookmark_from_document(
+    document: &Document,
+    document_fields: &BookmarkDocumentFields,
+) -> Result<Bookmark, TantivyBookmarkSearchEngineAdapterError> {
+    let id = Uuid::parse_str(stored_text(document, document_fields.id)?.as_str())
+        .map_err(|_| TantivyBookmarkSearchEngineAdapterError::InvalidFieldValue)?;
+    let url = Url::parse(stored_text(document, document_fields.url)?.as_str())
+        .map_err(|_| TantivyBookmarkSearchEngineAdapterError::InvalidFieldValue)?;
+    let description = document
+        .get_first(document_fields.description)
+        .and_then(|value| value.text())
+        .map(str::to_string);
+    let tags = document
+        .get_all(document_fields.tags)
+        .filter_map(|value| value.text())
+        .map(str::to_string)
+        .collect();
+    let aliases = document
+        .get_all(document_fields.aliases)
+        .filter_map(|value| value.text())
+        .map(str::to_string)
+        .collect();
+    let group_path = document
+        .get_all(document_fields.group_path)
+        .filter_map(|value| value.text())
+        .map(str::to_string)
+        .collect();
+
+    Ok(Bookmark::new(
+        id,
+        url,
+        stored_text(document, document_fields.name)?,
+        description,
+        tags,
+    )
+    .with_aliases(aliases)
+    .with_group_path(group_path)
+    .with_created_at(stored_date(document, document_fields.created_at))
+    .with_updated_at(stored_date(document, document_fields.updated_at)))
+}
+
+fn stored_date(document: &Document, field: Field) -> Option<DateTime<Utc>> {
+    document
+        .get_first(field)
+        .and_then(|value| value.date_value())
+        .cloned()
+}
+
+fn stored_text(
+    document: &Document,
+    field: Field,
+) -> Result<String, TantivyBookmarkSearchEngineAdapterError> {
+    document
+        .get_first(field)
+        .and_then(|value| value.text())
+        .map(str::to_string)
+        .ok_or(TantivyBookmarkSearchEngineAdapterError::MissingRequiredField)
+}
+
+fn content_hash(bookmark: &Bookmark) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bookmark.url().as_str());
+    hasher.update([0]);
+    hasher.update(bookmark.name());
+    hasher.update([0]);
+    if let Some(description) = bookmark.description() {
+        hasher.update([1]);
+        hasher.update(description);
+    }
+    hasher.update([0]);
+    bookmark.tags().iter().for_each(|tag| {
+        hasher.update(tag);
+        hasher.update([0]);
+    });
+    hasher.update([0]);
+    bookmark.aliases().iter().for_each(|alias| {
+        hasher.update(alias);
+        hasher.update([0]);
+    });
+    hasher.update([0]);
+    bookmark.group_path().iter().for_each(|group| {
+        hasher.update(group);
+        hasher.update([0]);
+    });
+    if let Some(created_at) = bookmark.created_at() {
+        hasher.update([1]);
+        hasher.update(created_at.timestamp().to_be_bytes());
+    }
+    hasher.update([0]);
+    if let Some(updated_at) = bookmark.updated_at() {
+        hasher.update([1]);
+        hasher.update(updated_at.timestamp().to_be_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use chrono::{TimeZone, Utc};
+    use spectral::prelude::*;
+
+    use crate::domain::bookmark::BookmarkRepository;
+    use crate::ports::persistence::sqlite::SqliteBookmarkRepositoryAdapter;
+    use crate::ports::search::tantivy::index_storage::remove_retired_index;
+
+    use super::*;
+
+    fn bookmark(name: &str, description: &str) -> Bookmark {
+        Bookmark::new(
+            Uuid::new_v4(),
+            Url::parse("https://example.com").unwrap(),
+            name.to_string(),
+            Some(description.to_string()),
+            vec![],
+        )
+    }
+
+    fn search_engine(
+        text_analysis: TextAnalysisConfig,
+        synonyms: Synonyms,
+        bookmarks: Vec<Bookmark>,
+    ) -> TantivyBookmarkSearchEngineAdapter {
+        persistent_search_engine(text_analysis, synonyms, None, bookmarks)
+    }
+
+    fn persistent_search_engine(
+        text_analysis: TextAnalysisConfig,
+        synonyms: Synonyms,
+        index_directory: Option<PathBuf>,
+        bookmarks: Vec<Bookmark>,
+    ) -> TantivyBookmarkSearchEngineAdapter {
+        let mut search_engine =
+            TantivyBookmarkSearchEngineAdapter::new(text_analysis, synonyms, index_directory);
+        search_engine.initialise(bookmarks).unwrap();
+        search_engine
+    }
+
+    fn names(bookmarks: Vec<Bookmark>) -> Vec<String> {
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.name().clone())
+            .collect()
+    }
+
+    #[test]
+    fn matches_inflected_terms_when_stemming_is_configured() {
+        let search_engine = search_engine(
+            TextAnalysisConfig::new(Some("english"), true, true, vec![]),
+            Synonyms::default(),
+            vec![bookmark("Deployment dashboard", "Release tracking")],
+        );
+
+        let results = search_engine.search("deploying".to_string()).unwrap();
+
+        assert_that(&names(results)).is_equal_to(vec!["Deployment dashboard".to_string()]);
+    }
+
+    #[test]
+    fn matches_accented_terms_when_ascii_folding_is_configured() {
+        let search_engine = search_engine(
+            TextAnalysisConfig::new(None, true, true, vec![]),
+            Synonyms::default(),
+            vec![bookmark("Café menu", "Lunch options")],
+        );
+
+        let results = search_engine.search("cafe".to_string()).unwrap();
+
+        assert_that(&names(results)).is_equal_to(vec!["Café menu".to_string()]);
+    }
+
+    #[test]
+    fn ignores_configured_stop_words() {
+        let search_engine = search_engine(
+            TextAnalysisConfig::new(None, true, true, vec!["The".to_string()]),
+            Synonyms::default(),
+            vec![bookmark("The wiki", "Team documentation")],
+        );
+
+        let results = search_engine.search("the".to_string()).unwrap();
+
+        assert_that(&results).is_empty();
+    }
+
+    #[test]
+    fn folds_stop_words_like_the_text_they_are_removed_from() {
+        let search_engine = search_engine(
+            TextAnalysisConfig::new(None, true, true, vec!["Über".to_string()]),
+            Synonyms::default(),
+            vec![bookmark("Uber dashboard", "Über alles")],
+        );
+
+        let results = search_engine.search("uber".to_string()).unwrap();
+
+        assert_that(&results).is_empty();
+    }
+
+    #[test]
+    fn finds_bookmarks_by_any_synonym_of_the_query_term() {
+        let search_engine = search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::new(vec![vec!["kubernetes".to_string(), "k8s".to_string()]]),
+            vec![bookmark("Kubernetes dashboard", "Cluster overview")],
+        );
+
+        let results = search_engine.search("k8s".to_string()).unwrap();
+
+        assert_that(&names(results)).is_equal_to(vec!["Kubernetes dashboard".to_string()]);
+    }
+
+    #[test]
+    fn finds_bookmarks_by_a_synonym_within_a_field() {
+        let mut tagged = bookmark("Cluster overview", "Dashboard");
+        tagged = Bookmark::new(
+            tagged.id(),
+            tagged.url().clone(),
+            tagged.name().clone(),
+            tagged.description().cloned(),
+            vec!["kubernetes".to_string()],
+        );
+        let search_engine = search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::new(vec![vec!["kubernetes".to_string(), "k8s".to_string()]]),
+            vec![tagged, bookmark("Kubernetes docs", "Reference")],
+        );
+
+        let results = search_engine.search("tags:k8s".to_string()).unwrap();
+
+        assert_that(&names(results)).is_equal_to(vec!["Cluster overview".to_string()]);
+    }
+
+    #[test]
+    fn applies_only_changed_bookmarks_to_an_existing_index_directory() {
+        let index_directory = tempfile::tempdir().unwrap();
+        let unchanged = bookmark("Wiki", "Team documentation");
+        let removed = bookmark("Legacy wiki", "Old documentation");
+        persistent_search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            Some(index_directory.path().to_path_buf()),
+            vec![unchanged.clone(), removed],
+        );
+
+        let search_engine = persistent_search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            Some(index_directory.path().to_path_buf()),
+            vec![unchanged, bookmark("Runbooks", "Incident documentation")],
+        );
+        let mut results = names(search_engine.search("documentation".to_string()).unwrap());
+        results.sort();
+
+        assert_that(&results).is_equal_to(vec!["Runbooks".to_string(), "Wiki".to_string()]);
+    }
+
+    #[test]
+    fn keeps_other_files_in_the_index_directory_when_rebuilding() {
+        let index_directory = tempfile::tempdir().unwrap();
+        let other_file = index_directory.path().join("visits.db");
+        std::fs::write(&other_file, "visits").unwrap();
+
+        persistent_search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            Some(index_directory.path().to_path_buf()),
+            vec![bookmark("Wiki", "Team documentation")],
+        );
+        persistent_search_engine(
+            TextAnalysisConfig::new(Some("english"), true, true, vec![]),
+            Synonyms::default(),
+            Some(index_directory.path().to_path_buf()),
+            vec![bookmark("Wiki", "Team documentation")],
+        );
+
+        assert_that(&std::fs::read_to_string(&other_file).unwrap())
+            .is_equal_to("visits".to_string());
+    }
+
+    #[test]
+    fn keeps_the_replaced_index_until_it_is_removed() {
+        let index_directory = tempfile::tempdir().unwrap();
+        let served = persistent_search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            Some(index_directory.path().to_path_buf()),
+            vec![bookmark("Wiki", "Team documentation")],
+        );
+
+        persistent_search_engine(
+            TextAnalysisConfig::new(Some("english"), true, true, vec![]),
+            Synonyms::default(),
+            Some(index_directory.path().to_path_buf()),
+            vec![bookmark("Runbooks", "Incident documentation")],
+        );
+        let retired = index_directory.path().join("tantivy.old");
+        let served_results = names(served.search("documentation".to_string()).unwrap());
+        let retired_before_removal = retired.exists();
+        remove_retired_index(Some(index_directory.path()));
+
+        assert_that(&served_results).is_equal_to(vec!["Wiki".to_string()]);
+        assert_that(&retired_before_removal).is_true();
+        assert_that(&retired.exists()).is_false();
+    }
+
+    #[test]
+    fn restores_the_replaced_index_when_the_new_one_cannot_be_opened() {
+        let index_directory = tempfile::tempdir().unwrap();
+        persistent_search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            Some(index_directory.path().to_path_buf()),
+            vec![bookmark("Wiki", "Team documentation")],
+        );
+        let schema = TantivyBookmarkSearchEngineAdapter::schema();
+        let fingerprint = index_fingerprint(&TextAnalysisConfig::default());
+
+        let index = open_index(Some(index_directory.path()), schema, "rebuild").unwrap();
+        let published = publish_index(Some(index_directory.path()), index, |_| {
+            Err::<(), _>(TantivyBookmarkSearchEngineAdapterError::MissingRequiredField)
+        });
+        let restored = Index::open_in_dir(index_directory.path().join("tantivy")).unwrap();
+
+        assert_that(&published.is_err()).is_true();
+        assert_that(&restored.load_metas().unwrap().payload).is_equal_to(Some(fingerprint));
+    }
+
+    #[test]
+    fn keeps_index_in_sync_with_individual_writes() {
+        let search_engine =
+            search_engine(TextAnalysisConfig::default(), Synonyms::default(), vec![]);
+        let wiki = bookmark("Wiki", "Team documentation");
+
+        search_engine.index(&wiki).unwrap();
+        let indexed = names(search_engine.search("documentation".to_string()).unwrap());
+        search_engine.remove(wiki.id()).unwrap();
+        let removed = search_engine.search("documentation".to_string()).unwrap();
+
+        assert_that(&indexed).is_equal_to(vec!["Wiki".to_string()]);
+        assert_that(&removed).is_empty();
+    }
+
+    #[test]
+    fn updates_indexed_bookmarks_only() {
+        let wiki = bookmark("Wiki", "Team documentation");
+        let search_engine = search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            vec![wiki.clone()],
+        );
+        let renamed = Bookmark::new(
+            wiki.id(),
+            wiki.url().clone(),
+            "Handbook".to_string(),
+            wiki.description().cloned(),
+            vec![],
+        );
+
+        search_engine.update(&renamed).unwrap();
+        let results = names(search_engine.search("documentation".to_string()).unwrap());
+        let unknown = search_engine.update(&bookmark("Runbooks", "Incident documentation"));
+
+        assert_that(&results).is_equal_to(vec!["Handbook".to_string()]);
+        assert_that(&matches!(
+            unknown,
+            Err(BookmarkSearchEngineError::BookmarkNotFound(_))
+        ))
+        .is_true();
+    }
+
+    #[test]
+    fn reconstructs_bookmarks_from_stored_fields() {
+        let mut wiki = bookmark("Wiki", "Team documentation");
+        wiki = Bookmark::new(
+            wiki.id(),
+            Url::parse("https://wiki.example.com/home").unwrap(),
+            wiki.name().clone(),
+            wiki.description().cloned(),
+            vec!["docs".to_string(), "team".to_string()],
+        )
+        .with_aliases(vec!["w".to_string()])
+        .with_group_path(vec!["Engineering".to_string(), "Docs".to_string()])
+        .with_created_at(Some(Utc.timestamp(1_600_000_000, 0)))
+        .with_updated_at(Some(Utc.timestamp(1_700_000_000, 0)));
+        let search_engine = search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            vec![wiki.clone()],
+        );
+
+        let results = search_engine.search("wiki".to_string()).unwrap();
+
+        assert_that(&results.len()).is_equal_to(1);
+        let result = &results[0];
+        assert_that(&result.id()).is_equal_to(wiki.id());
+        assert_that(result.url()).is_equal_to(wiki.url());
+        assert_that(&result.description()).is_equal_to(wiki.description());
+        assert_that(result.tags()).is_equal_to(wiki.tags());
+        assert_that(result.aliases()).is_equal_to(wiki.aliases());
+        assert_that(result.group_path()).is_equal_to(wiki.group_path());
+        assert_that(&result.created_at()).is_equal_to(wiki.created_at());
+        assert_that(&result.updated_at()).is_equal_to(wiki.updated_at());
+    }
+
+    #[test]
+    fn ranks_frequently_and_recently_visited_bookmarks_above_equal_text_matches() {
+        let staging = bookmark("Staging dashboard", "Metrics");
+        let production = bookmark("Production dashboard", "Metrics");
+        let search_engine = search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            vec![staging.clone(), production.clone()],
+        );
+        let visit = |bookmark: &Bookmark, days_ago: i64| {
+            BookmarkVisit::new(
+                bookmark.id(),
+                Utc::now() - chrono::Duration::days(days_ago),
+                None,
+            )
+        };
+
+        search_engine
+            .record_visits(&[visit(&staging, 1), visit(&staging, 2)])
+            .unwrap();
+        let visited_staging = names(search_engine.search("dashboard".to_string()).unwrap());
+        search_engine
+            .record_visits(&[visit(&production, 0), visit(&production, 0)])
+            .unwrap();
+        let visited_production = names(search_engine.search("dashboard".to_string()).unwrap());
+
+        assert_that(&visited_staging).is_equal_to(vec![
+            "Staging dashboard".to_string(),
+            "Production dashboard".to_string(),
+        ]);
+        assert_that(&visited_production).is_equal_to(vec![
+            "Production dashboard".to_string(),
+            "Staging dashboard".to_string(),
+        ]);
+    }
+
+    /// Search as it was before bookmarks were rebuilt from stored fields: every hit is looked up
+    /// by id in the bookmark repository.
+    fn search_with_repository_lookup(
+        search_engine: &TantivyBookmarkSearchEngineAdapter,
+        bookmark_repository: &dyn BookmarkRepository,
+        term: &str,
+    ) -> Vec<Bookmark> {
+        let initialised_engine = search_engine.initialised_engine();
+        let document_fields = &initialised_engine.document_fields;
+        let searcher = initialised_engine.reader.searcher();
+        let query = search_engine.parse_query(term).unwrap();
+
+        search_engine
+            .top_docs(&searcher, query.as_ref(), document_fields)
+            .unwrap()
+            .iter()
+            .map(|(_score, doc_address)| {
+                let document = searcher.doc(*doc_address).unwrap();
+                let id = stored_text(&document, document_fields.id).unwrap();
+                bookmark_repository
+                    .get(Uuid::parse_str(id.as_str()).unwrap())
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn time<F: FnMut()>(iterations: u32, mut operation: F) -> Duration {
+        let started = Instant::now();
+        (0..iterations).for_each(|_| operation());
+        started.elapsed() / iterations
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture search_latency`.
+    #[test]
+    #[ignore]
+    fn search_latency_on_50k_bookmarks() {
+        let bookmarks: Vec<Bookmark> = (0..50_000)
+            .map(|n| {
+                Bookmark::new(
+                    Uuid::new_v4(),
+                    Url::parse(&format!("https://service{}.example.com", n)).unwrap(),
+                    format!("Service {}", n),
+                    Some(format!("Dashboard group{}", n % 100)),
+                    vec![],
+                )
+            })
+            .collect();
+        let database_directory = tempfile::tempdir().unwrap();
+        let bookmark_repository =
+            SqliteBookmarkRepositoryAdapter::open(&database_directory.path().join("bookmarks.db"))
+                .unwrap();
+        bookmark_repository.import(&bookmarks).unwrap();
+        let search_engine = search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            bookmarks,
+        );
+        let term = "dashboard group42";
+
+        let stored_fields = search_engine.search(term.to_string()).unwrap();
+        let repository_lookup =
+            search_with_repository_lookup(&search_engine, &bookmark_repository, term);
+        let stored_fields_latency = time(100, || {
+            search_engine.search(term.to_string()).unwrap();
+        });
+        let repository_lookup_latency = time(100, || {
+            search_with_repository_lookup(&search_engine, &bookmark_repository, term);
+        });
+        println!(
+            "search over 50000 bookmarks: {:?} from stored fields, {:?} with repository lookups",
+            stored_fields_latency, repository_lookup_latency
+        );
+
+        assert_that(&stored_fields.len()).is_equal_to(SEARCH_LIMIT);
+        let ids =
+            |bookmarks: &Vec<Bookmark>| bookmarks.iter().map(Bookmark::id).collect::<Vec<_>>();
+        assert_that(&ids(&stored_fields)).is_equal_to(ids(&repository_lookup));
+        assert_that(&stored_fields_latency).is_less_than(repository_lookup_latency);
+    }
+
+    #[test]
+    fn ranks_bookmarks_by_their_own_visits_when_their_ids_share_a_half() {
+        let with_id = |id: u128, name: &str| {
+            Bookmark::new(
+                Uuid::from_u128(id),
+                Url::parse("https://example.com").unwrap(),
+                name.to_string(),
+                Some("Metrics".to_string()),
+                vec![],
+            )
+        };
+        let staging = with_id(1 << 64 | 7, "Staging dashboard");
+        let production = with_id(2 << 64 | 7, "Production dashboard");
+        let search_engine = search_engine(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            vec![staging.clone(), production.clone()],
+        );
+        let mut visits = vec![BookmarkVisit::new(production.id(), Utc::now(), None); 5];
+        visits.push(BookmarkVisit::new(staging.id(), Utc::now(), None));
+
+        search_engine.record_visits(&visits).unwrap();
+        let results = names(search_engine.search("dashboard".to_string()).unwrap());
+
+        assert_that(&results).is_equal_to(vec![
+            "Production dashboard".to_string(),
+            "Staging dashboard".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn ranks_by_text_relevance_alone_without_frecency_weight() {
+        let wiki = bookmark("Wiki", "Wiki documentation");
+        let runbooks = bookmark("Runbooks", "Incident documentation");
+        let mut search_engine = TantivyBookmarkSearchEngineAdapter::new(
+            TextAnalysisConfig::default(),
+            Synonyms::default(),
+            None,
+        )
+        .with_frecency(&FrecencyConfig::new(0.0, 30));
+        search_engine
+            .initialise(vec![wiki, runbooks.clone()])
+            .unwrap();
+
+        search_engine
+            .record_visits(&[BookmarkVisit::new(runbooks.id(), Utc::now(), None)])
+            .unwrap();
+        let results = names(
+            search_engine
+                .search("wiki documentation".to_string())
+                .unwrap(),
+        );
+
+        assert_that(&results).is_equal_to(vec!["Wiki".to_string(), "Runbooks".to_string()]);
     }
 }
@@ -0,0 +1,133 @@
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, Language, LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer,
+    StopWordFilter, TextAnalyzer,
+};
+
+pub(super) const BOOKMARK_TEXT_ANALYZER: &str = "bookmark_text";
+
+const MAX_TOKEN_LENGTH: usize = 40;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub(crate) struct TextAnalysisConfig {
+    language: Option<StemmerLanguage>,
+    ascii_folding: bool,
+    lowercase: bool,
+    stop_words: Vec<String>,
+}
+
+impl TextAnalysisConfig {
+    pub(super) fn text_analyzer(&self) -> TextAnalyzer {
+        let mut analyzer = self.normaliser();
+        if !self.stop_words.is_empty() {
+            analyzer = analyzer.filter(StopWordFilter::remove(self.normalised_stop_words()));
+        }
+        if let Some(language) = self.language {
+            analyzer = analyzer.filter(Stemmer::new(language.into()));
+        }
+        analyzer
+    }
+
+    fn normaliser(&self) -> TextAnalyzer {
+        let mut analyzer =
+            TextAnalyzer::from(SimpleTokenizer).filter(RemoveLongFilter::limit(MAX_TOKEN_LENGTH));
+        if self.lowercase {
+            analyzer = analyzer.filter(LowerCaser);
+        }
+        if self.ascii_folding {
+            analyzer = analyzer.filter(AsciiFoldingFilter);
+        }
+        analyzer
+    }
+
+    /// Stop words go through the same tokenizing, lowercasing and folding as the text they are
+    /// removed from, so that e.g. "Über" still removes "uber".
+    fn normalised_stop_words(&self) -> Vec<String> {
+        let normaliser = self.normaliser();
+        let mut stop_words = vec![];
+        self.stop_words.iter().for_each(|word| {
+            normaliser
+                .token_stream(word)
+                .process(&mut |token| stop_words.push(token.text.clone()));
+        });
+        stop_words
+    }
+}
+
+impl TextAnalysisConfig {
+    #[cfg(test)]
+    pub(crate) fn new(
+        language: Option<&str>,
+        ascii_folding: bool,
+        lowercase: bool,
+        stop_words: Vec<String>,
+    ) -> Self {
+        TextAnalysisConfig {
+            language: language.map(|language| {
+                serde_yaml::from_str(language).expect("unsupported stemmer language")
+            }),
+            ascii_folding,
+            lowercase,
+            stop_words,
+        }
+    }
+}
+
+impl Default for TextAnalysisConfig {
+    fn default() -> Self {
+        TextAnalysisConfig {
+            language: None,
+            ascii_folding: true,
+            lowercase: true,
+            stop_words: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum StemmerLanguage {
+    Arabic,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Romanian,
+    Russian,
+    Spanish,
+    Swedish,
+    Tamil,
+    Turkish,
+}
+
+impl From<StemmerLanguage> for Language {
+    fn from(language: StemmerLanguage) -> Self {
+        match language {
+            StemmerLanguage::Arabic => Language::Arabic,
+            StemmerLanguage::Danish => Language::Danish,
+            StemmerLanguage::Dutch => Language::Dutch,
+            StemmerLanguage::English => Language::English,
+            StemmerLanguage::Finnish => Language::Finnish,
+            StemmerLanguage::French => Language::French,
+            StemmerLanguage::German => Language::German,
+            StemmerLanguage::Greek => Language::Greek,
+            StemmerLanguage::Hungarian => Language::Hungarian,
+            StemmerLanguage::Italian => Language::Italian,
+            StemmerLanguage::Norwegian => Language::Norwegian,
+            StemmerLanguage::Portuguese => Language::Portuguese,
+            StemmerLanguage::Romanian => Language::Romanian,
+            StemmerLanguage::Russian => Language::Russian,
+            StemmerLanguage::Spanish => Language::Spanish,
+            StemmerLanguage::Swedish => Language::Swedish,
+            StemmerLanguage::Tamil => Language::Tamil,
+            StemmerLanguage::Turkish => Language::Turkish,
+        }
+    }
+}